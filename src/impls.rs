@@ -1,19 +1,31 @@
 #![allow(unused)]
 
 use crate::color_print::NumType;
-use crate::color_print::{Color as _, Exeptions};
-use crate::{Color as ColorStruct, ColorStandered};
+use crate::color_print::{
+    AdjustSpace, AlphaMode, ApcaResult, BlendMode, Color as _, ColorDepth, CompositeOperator,
+    DistanceMetric, Easing, Exeptions, Harmony, HueMethod, MonochromaticKind, NameSource,
+    NamedColor, UnderlineStyle, WcagResult, WhitePoint,
+};
+use crate::utils::quantize::Layer;
+use crate::utils::types::RGB;
+use crate::utils::value::ColorValue;
+use crate::utils::{blend, composite, contrast, difference, from_rgb, gradient, harmony};
+use crate::utils::{helper, names, quantize, terminal, to_rgb};
+use crate::{
+    Color as ColorStruct, ColorStandered, Gradient as GradientStruct, Style as StyleStruct,
+};
 use core::fmt::Display;
-use std::cell::Cell;
+use std::sync::{PoisonError, RwLock};
 
 impl Display for Exeptions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::IncorrectLength(len) => write!(
-                f,
-                "Expctd a length between 6 and 7 (inclusive), but got length {}",
-                len
-            ),
+            Self::IncorrectLength(len) => {
+                write!(f, "Expctd 3, 4, 6 or 8 hex digits, but got {} digits", len)
+            }
+            Self::InvalidHexDigit(position) => {
+                write!(f, "Invalid hex digit at position {}", position)
+            }
             Self::HueOutOfRange(value) => {
                 write!(f, "Expectd a value inbetween 0 and 360 but got {}", value)
             }
@@ -50,6 +62,50 @@ impl Display for Exeptions {
             Self::AlphaOutOfRange(value) => {
                 write!(f, "Expectd a value inbetween 0 and 1 but got {}", value)
             }
+            Self::LabLightnessOutOfRange(value) => {
+                write!(f, "Expected a value between 0 and 100 but got {}", value)
+            }
+            Self::ChromaOutOfRange(value) => {
+                write!(f, "Expected a positive value but got {}", value)
+            }
+            Self::XyzOutOfRange(value) => {
+                write!(f, "Expected a positive value but got {}", value)
+            }
+            Self::OklabLightnessOutOfRange(value) => {
+                write!(f, "Expected a value between 0 and 1 but got {}", value)
+            }
+            Self::OklabAOutOfRange(value) => {
+                write!(f, "Expected a value between -0.5 and 0.5 but got {}", value)
+            }
+            Self::OklabBOutOfRange(value) => {
+                write!(f, "Expected a value between -0.5 and 0.5 but got {}", value)
+            }
+            Self::OklchChromaOutOfRange(value) => {
+                write!(f, "Expected a value between 0 and 0.5 but got {}", value)
+            }
+            Self::InvalidCss(error) => write!(
+                f,
+                "Invalid css color ({:?}) at position {}",
+                error.kind, error.position
+            ),
+            Self::IncorrectPaletteLength(len) => {
+                write!(f, "Expected 16 palette colors, but got {} colors", len)
+            }
+            Self::NoGradientStops => write!(f, "Expected at least one gradient stop"),
+            Self::StopPositionOutOfRange(value) => {
+                write!(f, "Expected a value between 0 and 1 but got {}", value)
+            }
+            Self::UnorderedStopPosition(value) => {
+                write!(
+                    f,
+                    "Expected stop positions in ascending order but got {}",
+                    value
+                )
+            }
+            Self::UnknownColorName(name) => write!(f, "Unknown color name {}", name),
+            Self::StanderedWithoutHue(standered) => {
+                write!(f, "Expected a standard with a hue but got {:?}", standered)
+            }
         }
     }
 }
@@ -57,13 +113,35 @@ impl Display for Exeptions {
 impl std::error::Error for Exeptions {}
 
 impl Default for ColorStruct {
+    fn default() -> Self {
+        ColorValue::default().into()
+    }
+}
+
+impl Clone for ColorStruct {
+    fn clone(&self) -> Self {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+
+        Self {
+            values: RwLock::new(values.clone()),
+        }
+    }
+}
+
+impl Default for StyleStruct {
     fn default() -> Self {
         Self {
-            rgb: Cell::new(None),
-            cmyk: Cell::new(None),
-            hsl: Cell::new(None),
-            hsv: Cell::new(None),
-            kind: Cell::new(ColorStandered::None),
+            foreground: None,
+            background: None,
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
+            blink: false,
+            reverse: false,
+            hidden: false,
+            strikethrough: false,
         }
     }
 }
@@ -76,126 +154,163 @@ impl PartialEq for ColorStandered {
     }
 }
 
-impl ColorStruct {
-    pub fn new_cmyk(cyan: f64, magenta: f64, yellow: f64, black: f64) -> Result<Self, Exeptions> {
-        if !(0. ..=1.).contains(&cyan) {
-            return Err(Exeptions::CyanOutOfRange(cyan));
-        } else if !(0. ..=1.).contains(&magenta) {
-            return Err(Exeptions::MagentaOutOfRange(magenta));
-        } else if !(0. ..=1.).contains(&yellow) {
-            return Err(Exeptions::YellowOutOfRange(yellow));
-        } else if !(0. ..=1.).contains(&black) {
-            return Err(Exeptions::BlackOutOfRange(black));
+impl From<ColorValue> for ColorStruct {
+    fn from(value: ColorValue) -> Self {
+        Self {
+            values: RwLock::new(vec![value]),
         }
+    }
+}
 
-        Ok(Self {
-            cmyk: Cell::new(Some((cyan, magenta, yellow, black))),
-            kind: Cell::new(ColorStandered::Cmyk),
-            ..Self::default()
-        })
+impl ColorStruct {
+    pub fn new_cmyk(cyan: f64, magenta: f64, yellow: f64, black: f64) -> Result<Self, Exeptions> {
+        ColorValue::cmyk(cyan, magenta, yellow, black).map(Into::into)
     }
 
     pub fn new_hex(value: String) -> Result<Self, Exeptions> {
-        if value.len() != 6 && value.len() != 7 {
-            return Err(Exeptions::IncorrectLength(
-                value.len().try_into().expect("length to large"),
-            ));
-        }
-
-        let values: [u8; 4] = if value.starts_with('#') {
-            let value = &value[1..value.len() - 1];
-            i32::from_str_radix(value, 16)
-                .expect("invalid syntax")
-                .to_le_bytes()
-        } else {
-            i32::from_str_radix(value.as_str(), 16)
-                .expect("invalid syntax")
-                .to_le_bytes()
-        };
+        ColorValue::hex(&value).map(Into::into)
+    }
 
-        assert!(values[3] == 0);
+    pub fn new_css(value: String) -> Result<Self, Exeptions> {
+        ColorValue::css(&value).map(Into::into)
+    }
 
-        Ok(Self {
-            rgb: Cell::new(Some((
-                values[0] as NumType,
-                values[1] as NumType,
-                values[2] as NumType,
-            ))),
-            kind: Cell::new(ColorStandered::Rgb),
-            ..Self::default()
-        })
+    /// A color from the CSS keywords, then the X11 names, then the curated xkcd subset.
+    pub fn new_name(name: String) -> Result<Self, Exeptions> {
+        ColorValue::name(&name).map(Into::into)
     }
 
     pub fn new_hsl(hue: f64, sateration: f64, lightness: f64) -> Result<Self, Exeptions> {
-        if !(0. ..=360.).contains(&hue) {
-            return Err(Exeptions::HueOutOfRange(hue));
-        } else if !(0. ..=1.).contains(&sateration) {
-            return Err(Exeptions::SaterationOutOfRange(sateration));
-        } else if !(0. ..=1.).contains(&lightness) {
-            return Err(Exeptions::LightnessOutOfRange(lightness));
-        }
-
-        Ok(Self {
-            hsl: Cell::new(Some((hue, sateration, lightness))),
-            kind: Cell::new(ColorStandered::Hsl),
-            ..Self::default()
-        })
+        ColorValue::hsl(hue, sateration, lightness).map(Into::into)
     }
 
     pub fn new_hsv(hue: f64, sateration: f64, value: f64) -> Result<Self, Exeptions> {
-        if !(0. ..=360.).contains(&hue) {
-            return Err(Exeptions::HueOutOfRange(hue));
-        } else if !(0. ..=1.).contains(&sateration) {
-            return Err(Exeptions::SaterationOutOfRange(sateration));
-        } else if !(0. ..=1.).contains(&value) {
-            return Err(Exeptions::LightnessOutOfRange(value));
-        }
+        ColorValue::hsv(hue, sateration, value).map(Into::into)
+    }
 
-        Ok(Self {
-            hsv: Cell::new(Some((hue, sateration, value))),
-            kind: Cell::new(ColorStandered::Hsv),
-            ..Self::default()
-        })
+    pub fn new_xyz(x: f64, y: f64, z: f64) -> Result<Self, Exeptions> {
+        ColorValue::xyz(x, y, z).map(Into::into)
+    }
+
+    pub fn new_lab(
+        lightness: f64,
+        a: f64,
+        b: f64,
+        white_point: WhitePoint,
+    ) -> Result<Self, Exeptions> {
+        ColorValue::lab(lightness, a, b, white_point).map(Into::into)
+    }
+
+    pub fn new_lch(
+        lightness: f64,
+        chroma: f64,
+        hue: f64,
+        white_point: WhitePoint,
+    ) -> Result<Self, Exeptions> {
+        ColorValue::lch(lightness, chroma, hue, white_point).map(Into::into)
+    }
+
+    pub fn new_oklab(lightness: f64, a: f64, b: f64) -> Result<Self, Exeptions> {
+        ColorValue::oklab(lightness, a, b).map(Into::into)
+    }
+
+    pub fn new_oklch(lightness: f64, chroma: f64, hue: f64) -> Result<Self, Exeptions> {
+        ColorValue::oklch(lightness, chroma, hue).map(Into::into)
     }
 
     pub fn new_rgb(red: f64, green: f64, blue: f64) -> Result<Self, Exeptions> {
-        if !(0. ..=255.).contains(&red) {
-            return Err(Exeptions::RedOutOfRange(red));
-        } else if !(0. ..=255.).contains(&green) {
-            return Err(Exeptions::GreenOutOfRange(green));
-        } else if !(0. ..=255.).contains(&blue) {
-            return Err(Exeptions::BlueOutOfRange(blue));
-        }
+        ColorValue::rgb(red, green, blue).map(Into::into)
+    }
+
+    /// A snapshot of the color that later `as_standered` calls on the resource won't change.
+    pub fn value(&self) -> ColorValue {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+
+        values.last().copied().unwrap_or_default()
+    }
+
+    /// A color holding `value` as-is in `standered`, in the layout `stored_color` returns.
+    pub fn new_standered_unchecked(
+        standered: ColorStandered,
+        value: (NumType, NumType, NumType, Option<NumType>),
+    ) -> Self {
+        ColorValue::new_unchecked(standered, value, 1.).into()
+    }
+
+    /// The color as sRGB, converted from whichever standered it is currently stored in.
+    pub fn rgb_value(&self) -> RGB {
+        self.value().rgb_value()
+    }
+
+    /// The values stored for the current standered, with the black channel of cmyk as the 4th element.
+    pub fn stored_color(&self) -> (NumType, NumType, NumType, Option<NumType>) {
+        self.value().components()
+    }
+
+    /// The values stored for `standered` if the color has been in it, as the `to-*` getters
+    /// return.
+    pub fn stored_in(
+        &self,
+        standered: ColorStandered,
+    ) -> Option<(NumType, NumType, NumType, Option<NumType>)> {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+
+        values
+            .iter()
+            .find(|value| value.standered() == standered)
+            .map(ColorValue::components)
+    }
+
+    /// Like `stored_in` for the standereds with three components.
+    pub fn stored_three(&self, standered: ColorStandered) -> Option<(NumType, NumType, NumType)> {
+        self.stored_in(standered)
+            .map(|value| (value.0, value.1, value.2))
+    }
+
+    pub fn with_alpha_no_handle(&self, alpha: NumType) -> Result<Self, Exeptions> {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+        let values = values
+            .iter()
+            .map(|value| value.with_alpha(alpha))
+            .collect::<Result<_, _>>()?;
 
         Ok(Self {
-            rgb: Cell::new(Some((red, green, blue))),
-            kind: Cell::new(ColorStandered::Rgb),
-            ..Self::default()
+            values: RwLock::new(values),
         })
     }
 
-    pub fn to_string_no_handle(&self, background: Option<Self>) -> String {
-        if background.is_none() {
-            let forground = self.into_standered(ColorStandered::Rgb);
-            return format!(
-                "\x1b[38;2;{};{};{}m",
-                forground.0.trunc(),
-                forground.1.trunc(),
-                forground.2.trunc()
-            );
-        }
-
-        let forground = self.into_standered(ColorStandered::Rgb);
-        let background = background.unwrap().into_standered(ColorStandered::Rgb);
-
-        format!(
-            "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
-            forground.0.trunc(),
-            forground.1.trunc(),
-            forground.2.trunc(),
-            background.0.trunc(),
-            background.1.trunc(),
-            background.2.trunc()
+    /// The escape sequence selecting `self` as the foreground, along with `background` and the
+    /// `underline` color when given, quantized to `depth`. Without a depth the one detected for the
+    /// terminal is used.
+    pub fn to_string_no_handle(
+        &self,
+        background: Option<Self>,
+        underline: Option<Self>,
+        depth: Option<ColorDepth>,
+    ) -> String {
+        let depth = depth.unwrap_or_else(terminal::detect_depth);
+
+        [
+            Some((self.rgb_value(), Layer::Foreground)),
+            background.map(|background| (background.rgb_value(), Layer::Background)),
+            underline.map(|underline| (underline.rgb_value(), Layer::Underline)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|(rgb, layer)| quantize::sgr_parameters(rgb, depth, layer))
+        .map(|parameters| format!("\x1b[{}m", parameters))
+        .collect()
+    }
+
+    /// The APCA lightness contrast of `self` as text over `background`.
+    ///
+    /// A translucent text color is first composited over the background.
+    pub fn apca_contrast_no_handle(&self, background: &Self) -> ApcaResult {
+        let (text, background) = self.flatten_over(background);
+
+        contrast::apca_contrast(
+            contrast::apca_luminance(text),
+            contrast::apca_luminance(background),
         )
     }
 
@@ -204,17 +319,482 @@ impl ColorStruct {
         to_mix: Self,
         alpha: NumType,
     ) -> Result<Self, Exeptions> {
-        if !(0. ..=1.).contains(&alpha) {
-            return Err(Exeptions::AlphaOutOfRange(alpha));
+        self.with_alpha_no_handle(alpha)?.composite_no_handle(
+            &to_mix,
+            CompositeOperator::SrcOver,
+            AlphaMode::Straight,
+        )
+    }
+
+    /// Composites `self` onto `backdrop` with a Porter-Duff operator.
+    ///
+    /// With `AlphaMode::Premultiplied` the RGB channels of both colors are taken to already be
+    /// multiplied by their alpha, and the result is stored premultiplied too.
+    pub fn composite_no_handle(
+        &self,
+        backdrop: &Self,
+        operator: CompositeOperator,
+        mode: AlphaMode,
+    ) -> Result<Self, Exeptions> {
+        let input = |color: &Self| {
+            let (red, green, blue, _, alpha) = color.into_standered(ColorStandered::Rgb);
+
+            ((red, green, blue), alpha)
+        };
+
+        let (rgb, alpha) = composite::composite(input(self), input(backdrop), operator, mode);
+
+        Self::new_rgb(
+            rgb.0.clamp(0., 255.),
+            rgb.1.clamp(0., 255.),
+            rgb.2.clamp(0., 255.),
+        )?
+        .with_alpha_no_handle(alpha.clamp(0., 1.))
+    }
+
+    pub fn blend_no_handle(&self, backdrop: &Self, mode: BlendMode) -> Result<Self, Exeptions> {
+        let source = self.into_standered(ColorStandered::Rgb);
+        let backdrop = backdrop.into_standered(ColorStandered::Rgb);
+        let backdrop_rgb = (backdrop.0, backdrop.1, backdrop.2);
+
+        let blended = blend::blend((source.0, source.1, source.2), backdrop_rgb, mode);
+        let weight = backdrop.4;
+        let mixed = (
+            (1. - weight) * source.0 + weight * blended.0,
+            (1. - weight) * source.1 + weight * blended.1,
+            (1. - weight) * source.2 + weight * blended.2,
+        );
+
+        let (rgb, alpha) = composite::composite(
+            (mixed, source.4),
+            (backdrop_rgb, backdrop.4),
+            CompositeOperator::SrcOver,
+            AlphaMode::Straight,
+        );
+
+        Self::new_rgb(
+            rgb.0.clamp(0., 255.),
+            rgb.1.clamp(0., 255.),
+            rgb.2.clamp(0., 255.),
+        )?
+        .with_alpha_no_handle(alpha.clamp(0., 1.))
+    }
+
+    /// Mixes `t` of `other` into `self` in `space`, like CSS `color-mix()`, with `t` clamped to
+    /// `0..=1`. The result is stored in `space`, or RGB for `ColorStandered::None`.
+    pub fn mix_no_handle(
+        &self,
+        other: &Self,
+        t: NumType,
+        space: ColorStandered,
+        method: HueMethod,
+    ) -> Self {
+        gradient::sample(&[self.clone(), other.clone()], t, space, method).unwrap_or_default()
+    }
+
+    /// The colors of `harmony`, starting with this one, found by rotating the hue in `space`.
+    ///
+    /// `ColorStandered::None` rotates in HSL. Standereds without a hue are an error, and colors
+    /// rotated out of sRGB are gamut mapped back into it.
+    pub fn harmony_no_handle(
+        &self,
+        harmony: Harmony,
+        space: ColorStandered,
+    ) -> Result<Vec<Self>, Exeptions> {
+        let space = match space {
+            ColorStandered::None => ColorStandered::Hsl,
+            space => space,
+        };
+        let (first, second, third, fourth, alpha) = self.into_standered(space);
+
+        harmony::angles(harmony)
+            .iter()
+            .map(|degrees| {
+                let components =
+                    harmony::rotate_hue((first, second, third, fourth), space, *degrees)
+                        .ok_or(Exeptions::StanderedWithoutHue(space))?;
+                Ok(ColorValue::new_unchecked(space, components, alpha)
+                    .gamut_mapped()
+                    .into())
+            })
+            .collect()
+    }
+
+    /// `steps` colors starting with this one and mixed in `space` towards white for tints, black
+    /// for shades or gray for tones, stopping one step short of that color.
+    pub fn monochromatic_no_handle(
+        &self,
+        kind: MonochromaticKind,
+        steps: u32,
+        space: ColorStandered,
+    ) -> Vec<Self> {
+        let target = match kind {
+            MonochromaticKind::Tints => 255.,
+            MonochromaticKind::Shades => 0.,
+            MonochromaticKind::Tones => 128.,
+        };
+        let target =
+            Self::new_standered_unchecked(ColorStandered::Rgb, (target, target, target, None));
+
+        (0..steps)
+            .map(|step| {
+                let t = step as NumType / steps as NumType;
+                self.mix_no_handle(&target, t, space, HueMethod::Shorter)
+            })
+            .collect()
+    }
+
+    /// A copy with the lightness, saturation or chroma, and hue components in `space` passed
+    /// through `adjust`. Lightness and saturation are clamped to `0..=1` and chroma to positive
+    /// values afterwards, and the result is gamut mapped into sRGB.
+    /// Ignores amounts that are NaN or infinite, which would leave no meaningful color.
+    fn finite_amount(amount: NumType) -> NumType {
+        if amount.is_finite() {
+            amount
+        } else {
+            0.
+        }
+    }
+
+    fn adjusted(
+        &self,
+        space: AdjustSpace,
+        adjust: impl FnOnce(&mut NumType, &mut NumType, &mut NumType),
+    ) -> Self {
+        let standered = match space {
+            AdjustSpace::Hsl => ColorStandered::Hsl,
+            AdjustSpace::Oklch => ColorStandered::Oklch,
+        };
+        let (first, second, third, _, alpha) = self.into_standered(standered);
+
+        let components = match space {
+            AdjustSpace::Hsl => {
+                let (mut hue, mut sateration, mut lightness) = (first, second, third);
+                adjust(&mut lightness, &mut sateration, &mut hue);
+                (
+                    hue.rem_euclid(360.),
+                    sateration.clamp(0., 1.),
+                    lightness.clamp(0., 1.),
+                    None,
+                )
+            }
+            AdjustSpace::Oklch => {
+                let (mut lightness, mut chroma, mut hue) = (first, second, third);
+                adjust(&mut lightness, &mut chroma, &mut hue);
+                (
+                    lightness.clamp(0., 1.),
+                    chroma.max(0.),
+                    hue.rem_euclid(360.),
+                    None,
+                )
+            }
+        };
+
+        ColorValue::new_unchecked(standered, components, alpha)
+            .gamut_mapped()
+            .into()
+    }
+
+    /// Adds `amount` to the lightness, from 0 to 1 in both spaces.
+    pub fn lighten_no_handle(&self, amount: NumType, space: AdjustSpace) -> Self {
+        let amount = Self::finite_amount(amount);
+        self.adjusted(space, |lightness, _, _| *lightness += amount)
+    }
+
+    pub fn darken_no_handle(&self, amount: NumType, space: AdjustSpace) -> Self {
+        self.lighten_no_handle(-amount, space)
+    }
+
+    /// Adds `amount` to the saturation from 0 to 1 in HSL, or to the chroma in OKLCH.
+    pub fn saturate_no_handle(&self, amount: NumType, space: AdjustSpace) -> Self {
+        let amount = Self::finite_amount(amount);
+        self.adjusted(space, |_, sateration, _| *sateration += amount)
+    }
+
+    pub fn desaturate_no_handle(&self, amount: NumType, space: AdjustSpace) -> Self {
+        self.saturate_no_handle(-amount, space)
+    }
+
+    pub fn rotate_hue_no_handle(&self, degrees: NumType, space: AdjustSpace) -> Self {
+        let degrees = Self::finite_amount(degrees);
+        self.adjusted(space, |_, _, hue| *hue += degrees)
+    }
+
+    pub fn grayscale_no_handle(&self, space: AdjustSpace) -> Self {
+        self.adjusted(space, |_, sateration, _| *sateration = 0.)
+    }
+
+    /// The sRGB negative, keeping alpha.
+    pub fn invert_no_handle(&self) -> Self {
+        let rgb = self.rgb_value();
+        let components = (255. - rgb.0, 255. - rgb.1, 255. - rgb.2, None);
+
+        ColorValue::new_unchecked(ColorStandered::Rgb, components, self.get_alpha()).into()
+    }
+
+    /// Moves the color by `amount` along OKLab's orange to blue axis, at a hue of 60 degrees.
+    /// Positive amounts are warmer and negative ones cooler; 0.05 is a gentle shift. The result is
+    /// gamut mapped into sRGB.
+    pub fn adjust_temperature_no_handle(&self, amount: NumType) -> Self {
+        let amount = Self::finite_amount(amount);
+        let (lightness, a, b, _, alpha) = self.into_standered(ColorStandered::Oklab);
+        let direction = (60. as NumType).to_radians();
+        let components = (
+            lightness,
+            a + amount * direction.cos(),
+            b + amount * direction.sin(),
+            None,
+        );
+
+        ColorValue::new_unchecked(ColorStandered::Oklab, components, alpha)
+            .gamut_mapped()
+            .into()
+    }
+
+    /// The perceptual difference between `self` and `other`, ignoring alpha.
+    ///
+    /// The Lab based metrics use the D50 white point, like CSS. For CMC `self` is the reference,
+    /// and the weights must be positive; zero or negative ones give infinity or NaN.
+    pub fn distance_no_handle(&self, other: &Self, metric: DistanceMetric) -> NumType {
+        let lab = |color: &Self| color.into_lab(WhitePoint::D50);
+
+        match metric {
+            DistanceMetric::Cie76 => difference::delta_e_76(lab(self), lab(other)),
+            DistanceMetric::Cie94GraphicArts => {
+                difference::delta_e_94(lab(self), lab(other), false)
+            }
+            DistanceMetric::Cie94Textiles => difference::delta_e_94(lab(self), lab(other), true),
+            DistanceMetric::Ciede2000 => difference::delta_e_2000(lab(self), lab(other)),
+            DistanceMetric::Cmc(weights) => {
+                difference::delta_e_cmc(lab(self), lab(other), weights.lightness, weights.chroma)
+            }
+            DistanceMetric::Itp => {
+                let rgb1 = self.rgb_value();
+                let rgb2 = other.rgb_value();
+
+                difference::delta_e_itp(
+                    from_rgb::rgb_to_xyz(rgb1.0, rgb1.1, rgb1.2),
+                    from_rgb::rgb_to_xyz(rgb2.0, rgb2.1, rgb2.2),
+                )
+            }
+        }
+    }
+
+    /// The CSS or X11 name, or the name from the curated xkcd subset when `include_xkcd_subset` is
+    /// set, closest to the color by `metric`. On a tie the CSS name wins over the X11 one, and both
+    /// over the xkcd one.
+    pub fn nearest_name_no_handle(
+        &self,
+        metric: DistanceMetric,
+        include_xkcd_subset: bool,
+    ) -> NamedColor {
+        let sources: &[NameSource] = if include_xkcd_subset {
+            &[NameSource::Css, NameSource::X11, NameSource::Xkcd]
+        } else {
+            &[NameSource::Css, NameSource::X11]
+        };
+
+        sources
+            .iter()
+            .flat_map(|source| {
+                names::table(*source)
+                    .iter()
+                    .map(move |(name, value)| (*source, *name, helper::unpack_rgb(*value)))
+            })
+            .map(|(source, name, rgb)| {
+                let candidate =
+                    Self::new_standered_unchecked(ColorStandered::Rgb, (rgb.0, rgb.1, rgb.2, None));
+                (self.distance_no_handle(&candidate, metric), source, name)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, source, name)| NamedColor {
+                name: name.to_string(),
+                source,
+            })
+            .unwrap_or_else(|| unreachable!("the name tables are not empty"))
+    }
+
+    pub fn relative_luminance_no_handle(&self) -> NumType {
+        contrast::relative_luminance(self.rgb_value())
+    }
+
+    /// The WCAG 2.x contrast ratio between `self`, as the foreground, and `background`.
+    ///
+    /// A translucent foreground is first composited over the background.
+    pub fn contrast_ratio_no_handle(&self, background: &Self) -> NumType {
+        let (foreground, background) = self.flatten_over(background);
+
+        contrast::contrast_ratio(
+            contrast::relative_luminance(foreground),
+            contrast::relative_luminance(background),
+        )
+    }
+
+    pub fn wcag_contrast_no_handle(&self, background: &Self) -> WcagResult {
+        contrast::wcag(self.contrast_ratio_no_handle(background))
+    }
+
+    /// `self` composited over an opaque `background`, along with that background's RGB.
+    fn flatten_over(&self, background: &Self) -> (RGB, RGB) {
+        let foreground = self.into_standered(ColorStandered::Rgb);
+        let background = background.rgb_value();
+
+        let (foreground, _) = composite::composite(
+            ((foreground.0, foreground.1, foreground.2), foreground.4),
+            (background, 1.),
+            CompositeOperator::SrcOver,
+            AlphaMode::Straight,
+        );
+
+        (foreground, background)
+    }
+}
+
+impl StyleStruct {
+    /// Wraps `text` in one sequence turning the style on and one turning exactly that back off.
+    ///
+    /// Without a depth the one detected for the terminal is used. `ColorDepth::None` leaves the
+    /// text unstyled.
+    pub fn paint_no_handle(&self, text: &str, depth: Option<ColorDepth>) -> String {
+        let depth = depth.unwrap_or_else(terminal::detect_depth);
+        if depth == ColorDepth::None {
+            return text.to_string();
+        }
+
+        let mut open = Vec::new();
+        let mut close = Vec::new();
+
+        if self.bold {
+            open.push("1".to_string());
+        }
+        if self.dim {
+            open.push("2".to_string());
+        }
+        if self.bold || self.dim {
+            close.push("22".to_string());
+        }
+        if self.italic {
+            open.push("3".to_string());
+            close.push("23".to_string());
+        }
+        let underline = match self.underline {
+            UnderlineStyle::None => None,
+            UnderlineStyle::Single => Some("4"),
+            UnderlineStyle::Double => Some("4:2"),
+            UnderlineStyle::Curly => Some("4:3"),
+            UnderlineStyle::Dotted => Some("4:4"),
+            UnderlineStyle::Dashed => Some("4:5"),
+        };
+        if let Some(underline) = underline {
+            open.push(underline.to_string());
+            close.push("24".to_string());
+        }
+        if self.blink {
+            open.push("5".to_string());
+            close.push("25".to_string());
+        }
+        if self.reverse {
+            open.push("7".to_string());
+            close.push("27".to_string());
+        }
+        if self.hidden {
+            open.push("8".to_string());
+            close.push("28".to_string());
+        }
+        if self.strikethrough {
+            open.push("9".to_string());
+            close.push("29".to_string());
+        }
+        if let Some(foreground) = &self.foreground {
+            open.extend(quantize::sgr_parameters(
+                foreground.rgb_value(),
+                depth,
+                Layer::Foreground,
+            ));
+            close.push("39".to_string());
+        }
+        if let Some(background) = &self.background {
+            open.extend(quantize::sgr_parameters(
+                background.rgb_value(),
+                depth,
+                Layer::Background,
+            ));
+            close.push("49".to_string());
+        }
+        if let Some(underline_color) = &self.underline_color {
+            open.extend(quantize::sgr_parameters(
+                underline_color.rgb_value(),
+                depth,
+                Layer::Underline,
+            ));
+            close.push("59".to_string());
+        }
+
+        if open.is_empty() {
+            return text.to_string();
+        }
+
+        format!("\x1b[{}m{}\x1b[{}m", open.join(";"), text, close.join(";"))
+    }
+}
+
+impl GradientStruct {
+    /// A gradient through `stops`, given as colors with positions from 0 to 1 in ascending order.
+    pub fn new_no_handle(
+        stops: Vec<(ColorStruct, NumType)>,
+        space: ColorStandered,
+        hue_method: HueMethod,
+    ) -> Result<Self, Exeptions> {
+        if stops.is_empty() {
+            return Err(Exeptions::NoGradientStops);
         }
 
-        let fg = self.into_standered(ColorStandered::Rgb);
-        let bg = to_mix.into_standered(ColorStandered::Rgb);
+        let mut previous = 0.;
+        for (_, position) in &stops {
+            if !(0. ..=1.).contains(position) {
+                return Err(Exeptions::StopPositionOutOfRange(*position));
+            }
+            if *position < previous {
+                return Err(Exeptions::UnorderedStopPosition(*position));
+            }
+            previous = *position;
+        }
+
+        Ok(Self {
+            stops,
+            space,
+            hue_method,
+            easing: Easing::Linear,
+        })
+    }
+
+    /// A gradient through `colors`, spaced evenly from 0 to 1.
+    pub fn from_colors_no_handle(
+        colors: Vec<ColorStruct>,
+        space: ColorStandered,
+        hue_method: HueMethod,
+    ) -> Result<Self, Exeptions> {
+        let steps = colors.len().saturating_sub(1).max(1) as NumType;
+        let stops = colors
+            .into_iter()
+            .enumerate()
+            .map(|(index, color)| (color, index as NumType / steps))
+            .collect();
+
+        Self::new_no_handle(stops, space, hue_method)
+    }
+
+    pub fn sample_no_handle(&self, t: NumType) -> ColorStruct {
+        gradient::sample_positioned(&self.stops, t, self.space, self.hue_method, self.easing)
+            .unwrap_or_default()
+    }
 
-        let r = ((fg.0 * alpha) + bg.0) / 2.;
-        let g = ((fg.1 * alpha) + bg.1) / 2.;
-        let b = ((fg.2 * alpha) + bg.2) / 2.;
+    /// `count` colors spaced evenly from the start to the end of the gradient.
+    pub fn colors_no_handle(&self, count: u32) -> Vec<ColorStruct> {
+        let steps = count.saturating_sub(1).max(1) as NumType;
 
-        Self::new_rgb(r, g, b)
+        (0..count)
+            .map(|index| self.sample_no_handle(index as NumType / steps))
+            .collect()
     }
 }
@@ -2,7 +2,11 @@ mod impls;
 mod resourses;
 mod utils;
 
-use crate::color_print::ColorStandered;
-use resourses::{Color, ColorPrint};
+use resourses::{Color, ColorPrint, Gradient, Style};
+
+pub use crate::color_print::{
+    ColorStandered, CssError, CssErrorKind, Exeptions, NumType, WhitePoint,
+};
+pub use utils::value::ColorValue;
 
 wai_bindgen_rust::export!("color_print.wai");
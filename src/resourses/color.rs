@@ -1,457 +1,341 @@
-use crate::color_print::{ColorStandered, Exeptions, NumType};
+use crate::color_print::{
+    AdjustSpace, AlphaMode, ApcaResult, BlendMode, ColorDepth, ColorStandered, CompositeOperator,
+    DistanceMetric, Exeptions, Harmony, HueMethod, MonochromaticKind, NamedColor, NumType,
+    WcagResult, WhitePoint,
+};
 use crate::utils::types::*;
-use crate::utils::{from_rgb, to_rgb};
-use std::cell::Cell;
+use crate::utils::value::ColorValue;
+use crate::utils::{from_rgb, helper};
+use std::sync::{PoisonError, RwLock};
 use wai_bindgen_rust::Handle;
 
+/// The wai resource, wrapping the `ColorValue` of every standered the color has been converted to
+/// with `as_standered`, the current one last. The `to-*` getters return the earlier ones too.
+///
+/// The lock lets `as_standered` convert a shared handle in place, and keeps the resource `Send`
+/// and `Sync`.
 pub struct Color {
-    pub rgb: Wrapper<(NumType, NumType, NumType)>,
-    pub cmyk: Wrapper<(NumType, NumType, NumType, NumType)>,
-    pub hsl: Wrapper<(NumType, NumType, NumType)>,
-    pub hsv: Wrapper<(NumType, NumType, NumType)>,
-    pub kind: Cell<ColorStandered>,
+    pub values: RwLock<Vec<ColorValue>>,
 }
 
 impl crate::color_print::Color for Color {
-    fn from_cmyk(cyan: f64, magenta: f64, yellow: f64, black: f64) -> NewColorResult {
-        if !(0. ..=1.).contains(&cyan) {
-            return Err(Exeptions::CyanOutOfRange(cyan));
-        } else if !(0. ..=1.).contains(&magenta) {
-            return Err(Exeptions::MagentaOutOfRange(magenta));
-        } else if !(0. ..=1.).contains(&yellow) {
-            return Err(Exeptions::YellowOutOfRange(yellow));
-        } else if !(0. ..=1.).contains(&black) {
-            return Err(Exeptions::BlackOutOfRange(black));
-        }
-
-        Ok(Self {
-            cmyk: Cell::new(Some((cyan, magenta, yellow, black))),
-            kind: Cell::new(ColorStandered::Cmyk),
-            ..Self::default()
-        }
-        .into())
+    fn from_cmyk(
+        cyan: f64,
+        magenta: f64,
+        yellow: f64,
+        black: f64,
+        alpha: Option<f64>,
+    ) -> NewColorResult {
+        Self::new_cmyk(cyan, magenta, yellow, black)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
     }
 
     fn from_hex(value: String) -> NewColorResult {
-        if value.len() != 6 && value.len() != 7 {
-            return Err(Exeptions::IncorrectLength(
-                value.len().try_into().expect("length to large"),
-            ));
-        }
+        Self::new_hex(value).map(Into::into)
+    }
 
-        let values: [u8; 4] = if value.starts_with('#') {
-            let value = &value[1..value.len() - 1];
-            i32::from_str_radix(value, 16)
-                .expect("invalid syntax")
-                .to_le_bytes()
-        } else {
-            i32::from_str_radix(value.as_str(), 16)
-                .expect("invalid syntax")
-                .to_le_bytes()
-        };
+    fn from_css(value: String) -> NewColorResult {
+        Self::new_css(value).map(Into::into)
+    }
 
-        assert!(values[3] == 0);
+    fn from_name(name: String) -> NewColorResult {
+        Self::new_name(name).map(Into::into)
+    }
 
-        Ok(Self {
-            rgb: Cell::new(Some((
-                values[0] as NumType,
-                values[1] as NumType,
-                values[2] as NumType,
-            ))),
-            kind: Cell::new(ColorStandered::Rgb),
-            ..Self::default()
-        }
-        .into())
+    fn from_hsl(hue: f64, sateration: f64, lightness: f64, alpha: Option<f64>) -> NewColorResult {
+        Self::new_hsl(hue, sateration, lightness)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
     }
 
-    fn from_hsl(hue: f64, sateration: f64, lightness: f64) -> NewColorResult {
-        if !(0. ..=360.).contains(&hue) {
-            return Err(Exeptions::HueOutOfRange(hue));
-        } else if !(0. ..=1.).contains(&sateration) {
-            return Err(Exeptions::SaterationOutOfRange(sateration));
-        } else if !(0. ..=1.).contains(&lightness) {
-            return Err(Exeptions::LightnessOutOfRange(lightness));
-        }
+    fn from_hsv(hue: f64, sateration: f64, value: f64, alpha: Option<f64>) -> NewColorResult {
+        Self::new_hsv(hue, sateration, value)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
+    }
 
-        Ok(Self {
-            hsl: Cell::new(Some((hue, sateration, lightness))),
-            kind: Cell::new(ColorStandered::Hsl),
-            ..Self::default()
-        }
-        .into())
+    fn from_rgb(red: f64, green: f64, blue: f64, alpha: Option<f64>) -> NewColorResult {
+        Self::new_rgb(red, green, blue)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
     }
 
-    fn from_hsv(hue: f64, sateration: f64, value: f64) -> NewColorResult {
-        if !(0. ..=360.).contains(&hue) {
-            return Err(Exeptions::HueOutOfRange(hue));
-        } else if !(0. ..=1.).contains(&sateration) {
-            return Err(Exeptions::SaterationOutOfRange(sateration));
-        } else if !(0. ..=1.).contains(&value) {
-            return Err(Exeptions::LightnessOutOfRange(value));
-        }
+    fn from_xyz(x: f64, y: f64, z: f64, alpha: Option<f64>) -> NewColorResult {
+        Self::new_xyz(x, y, z)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
+    }
 
-        Ok(Self {
-            hsv: Cell::new(Some((hue, sateration, value))),
-            kind: Cell::new(ColorStandered::Hsv),
-            ..Self::default()
-        }
-        .into())
+    fn from_lab(
+        lightness: f64,
+        a: f64,
+        b: f64,
+        white_point: WhitePoint,
+        alpha: Option<f64>,
+    ) -> NewColorResult {
+        Self::new_lab(lightness, a, b, white_point)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
     }
 
-    fn from_rgb(red: f64, green: f64, blue: f64) -> NewColorResult {
-        if !(0. ..=255.).contains(&red) {
-            return Err(Exeptions::RedOutOfRange(red));
-        } else if !(0. ..=255.).contains(&green) {
-            return Err(Exeptions::GreenOutOfRange(green));
-        } else if !(0. ..=255.).contains(&blue) {
-            return Err(Exeptions::BlueOutOfRange(blue));
-        }
+    fn from_lch(
+        lightness: f64,
+        chroma: f64,
+        hue: f64,
+        white_point: WhitePoint,
+        alpha: Option<f64>,
+    ) -> NewColorResult {
+        Self::new_lch(lightness, chroma, hue, white_point)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
+    }
 
-        Ok(Self {
-            rgb: Cell::new(Some((red, green, blue))),
-            kind: Cell::new(ColorStandered::Rgb),
-            ..Self::default()
-        }
-        .into())
+    fn from_oklab(lightness: f64, a: f64, b: f64, alpha: Option<f64>) -> NewColorResult {
+        Self::new_oklab(lightness, a, b)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
+    }
+
+    fn from_oklch(lightness: f64, chroma: f64, hue: f64, alpha: Option<f64>) -> NewColorResult {
+        Self::new_oklch(lightness, chroma, hue)?
+            .with_alpha_no_handle(alpha.unwrap_or(1.))
+            .map(Into::into)
     }
 
     fn new(red: f64, green: f64, blue: f64) -> NewColorResult {
-        Self::from_rgb(red, green, blue)
+        Self::from_rgb(red, green, blue, None)
     }
 
     fn new_unchecked(red: f64, green: f64, blue: f64) -> Handle<Color> {
-        Self {
-            rgb: Cell::new(Some((red, green, blue))),
-            kind: Cell::new(ColorStandered::Rgb),
-            ..Self::default()
-        }
-        .into()
+        Self::new_standered_unchecked(ColorStandered::Rgb, (red, green, blue, None)).into()
     }
 
     fn to_cmyk(&self) -> Option<CMYK> {
-        self.cmyk.get()
+        self.stored_in(ColorStandered::Cmyk)
+            .map(|cmyk| (cmyk.0, cmyk.1, cmyk.2, cmyk.3.unwrap_or_default()))
     }
 
     fn to_hsl(&self) -> Option<HSL> {
-        self.hsl.get()
+        self.stored_three(ColorStandered::Hsl)
     }
 
     fn to_hsv(&self) -> Option<HSV> {
-        self.hsv.get()
+        self.stored_three(ColorStandered::Hsv)
     }
 
     fn to_rgb(&self) -> Option<RGB> {
-        self.rgb.get()
+        self.stored_three(ColorStandered::Rgb)
+    }
+
+    fn to_xyz(&self) -> Option<XYZ> {
+        self.stored_three(ColorStandered::Xyz)
+    }
+
+    fn to_lab(&self) -> Option<LAB> {
+        self.stored_three(ColorStandered::Lab)
+    }
+
+    fn to_lch(&self) -> Option<LCH> {
+        self.stored_three(ColorStandered::Lch)
+    }
+
+    fn to_oklab(&self) -> Option<OKLAB> {
+        self.stored_three(ColorStandered::Oklab)
+    }
+
+    fn to_oklch(&self) -> Option<OKLCH> {
+        self.stored_three(ColorStandered::Oklch)
+    }
+
+    fn into_lab(&self, white_point: WhitePoint) -> LAB {
+        let rgb = self.rgb_value();
+
+        from_rgb::rgb_to_lab_with_white_point(rgb.0, rgb.1, rgb.2, white_point)
+    }
+
+    fn into_lch(&self, white_point: WhitePoint) -> LCH {
+        helper::to_polar(self.into_lab(white_point))
     }
 
     fn as_standered(&self, standered: ColorStandered) {
-        let kind = self.get_standered();
+        if standered == ColorStandered::None {
+            return;
+        }
 
-        if standered == kind || standered == ColorStandered::None {
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        let Some(&current) = values.last() else {
+            return;
+        };
+        if current.standered() == standered {
             return;
         }
 
-        match standered {
-            ColorStandered::Cmyk => match kind {
-                ColorStandered::Hsl => {
-                    let hsl = self.hsl.get().unwrap_or_default();
-                    let rgb = to_rgb::hsl_to_rgb(hsl.0, hsl.1, hsl.2);
-                    let cmyk = from_rgb::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+        values.retain(|value| value.standered() != standered);
+        values.push(current.into_standered(standered));
+    }
 
-                    self.cmyk.replace(Some(cmyk));
-                    //cmyk_ref.replace(cmyk);
-                }
-                ColorStandered::Hsv => {
-                    let hsv = self.hsv.get().unwrap_or_default();
-                    let rgb = to_rgb::hsv_to_rgb(hsv.0, hsv.1, hsv.2);
-                    let cmyk = from_rgb::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+    fn get_standered(&self) -> ColorStandered {
+        self.value().standered()
+    }
 
-                    self.cmyk.replace(Some(cmyk));
-                }
-                ColorStandered::Rgb => {
-                    let rgb = self.rgb.get().unwrap_or_default();
-                    let cmyk = from_rgb::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+    fn get_alpha(&self) -> NumType {
+        self.value().alpha()
+    }
 
-                    self.cmyk.replace(Some(cmyk));
-                }
-                _ => {}
-            },
-            ColorStandered::Hsl => match kind {
-                ColorStandered::Cmyk => {
-                    let origin = self.cmyk.get().unwrap_or_default();
-                    let rgb = to_rgb::cmyk_to_rgb(origin.0, origin.1, origin.2, origin.3);
-                    let end = from_rgb::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
-
-                    self.hsl.replace(Some(end));
-                }
-                ColorStandered::Hsv => {
-                    let origin = self.hsv.get().unwrap_or_default();
-                    let rgb = to_rgb::hsv_to_rgb(origin.0, origin.1, origin.2);
-                    let end = from_rgb::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+    fn with_alpha(&self, alpha: NumType) -> NewColorResult {
+        self.with_alpha_no_handle(alpha).map(Into::into)
+    }
 
-                    self.hsl.replace(Some(end));
-                }
-                ColorStandered::Rgb => {
-                    let origin = self.rgb.get().unwrap_or_default();
-                    let rgb = origin;
-                    let end = from_rgb::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+    fn get_internel_color(&self) -> InternelColor {
+        let color = self.stored_color();
 
-                    self.hsl.replace(Some(end));
-                }
-                _ => {}
-            },
-            ColorStandered::Hsv => match kind {
-                ColorStandered::Cmyk => {
-                    let origin = self.cmyk.get().unwrap_or_default();
-                    let rgb = to_rgb::cmyk_to_rgb(origin.0, origin.1, origin.2, origin.3);
-                    let end = from_rgb::rgb_to_hsv(rgb.0, rgb.1, rgb.2);
-
-                    self.hsv.replace(Some(end));
-                }
-                ColorStandered::Hsl => {
-                    let origin = self.hsl.get().unwrap_or_default();
-                    let rgb = to_rgb::hsl_to_rgb(origin.0, origin.1, origin.2);
-                    let end = from_rgb::rgb_to_hsv(rgb.0, rgb.1, rgb.2);
+        (color.0, color.1, color.2, color.3, self.get_alpha())
+    }
 
-                    self.hsv.replace(Some(end));
-                }
-                ColorStandered::Rgb => {
-                    let origin = self.rgb.get().unwrap_or_default();
-                    let rgb = origin;
-                    let end = from_rgb::rgb_to_hsv(rgb.0, rgb.1, rgb.2);
+    fn into_standered(&self, standered: ColorStandered) -> InternelColor {
+        let color = self.value().into_standered(standered);
+        let (a, b, c, d) = color.components();
 
-                    self.hsv.replace(Some(end));
-                }
-                _ => {}
-            },
-            ColorStandered::Rgb => match kind {
-                ColorStandered::Cmyk => {
-                    let origin = self.cmyk.get().unwrap_or_default();
-                    let rgb = to_rgb::cmyk_to_rgb(origin.0, origin.1, origin.2, origin.3);
-
-                    self.rgb.replace(Some(rgb));
-                }
-                ColorStandered::Hsl => {
-                    let origin = self.hsl.get().unwrap_or_default();
-                    let rgb = to_rgb::hsl_to_rgb(origin.0, origin.1, origin.2);
+        (a, b, c, d, color.alpha())
+    }
 
-                    self.rgb.replace(Some(rgb));
-                }
-                ColorStandered::Hsv => {
-                    let origin = self.hsv.get().unwrap_or_default();
-                    let rgb = to_rgb::hsv_to_rgb(origin.0, origin.1, origin.2);
+    fn to_string(
+        &self,
+        background: Option<Handle<Self>>,
+        underline: Option<Handle<Self>>,
+        depth: Option<ColorDepth>,
+    ) -> String {
+        self.to_string_no_handle(
+            background.map(|background| (*background).clone()),
+            underline.map(|underline| (*underline).clone()),
+            depth,
+        )
+    }
 
-                    self.rgb.replace(Some(rgb));
-                }
-                _ => {}
-            },
-            _ => {}
-        }
+    fn apca_contrast(&self, background: Handle<Color>) -> ApcaResult {
+        self.apca_contrast_no_handle(&background)
+    }
 
-        self.kind.replace(standered);
+    fn into_rgb_with_alpha(&self, to_mix: Handle<Color>, alpha: NumType) -> NewColorResult {
+        self.with_alpha_no_handle(alpha)?.composite(
+            to_mix,
+            CompositeOperator::SrcOver,
+            AlphaMode::Straight,
+        )
     }
 
-    fn get_standered(&self) -> ColorStandered {
-        self.kind.get()
+    fn composite(
+        &self,
+        backdrop: Handle<Color>,
+        operator: CompositeOperator,
+        mode: AlphaMode,
+    ) -> NewColorResult {
+        self.composite_no_handle(&backdrop, operator, mode)
+            .map(Into::into)
     }
 
-    fn get_internel_color(&self) -> (NumType, NumType, NumType, Option<NumType>) {
-        match self.get_standered() {
-            ColorStandered::Rgb => {
-                let rgb = self.to_rgb().unwrap();
-                (rgb.0, rgb.1, rgb.2, None)
-            }
-            ColorStandered::Hsl => {
-                let hsl = self.to_hsl().unwrap();
-                (hsl.0, hsl.1, hsl.2, None)
-            }
-            ColorStandered::Hsv => {
-                let hsv = self.to_hsv().unwrap();
-                (hsv.0, hsv.1, hsv.2, None)
-            }
-            ColorStandered::Cmyk => {
-                let cmyk = self.to_cmyk().unwrap();
-                (cmyk.0, cmyk.1, cmyk.2, Some(cmyk.3))
-            }
-            ColorStandered::None => (0., 0., 0., None),
-        }
+    fn blend(&self, backdrop: Handle<Color>, mode: BlendMode) -> NewColorResult {
+        self.blend_no_handle(&backdrop, mode).map(Into::into)
     }
 
-    fn into_standered(
+    fn mix(
         &self,
-        standered: ColorStandered,
-    ) -> (NumType, NumType, NumType, Option<NumType>) {
-        let kind = self.get_standered();
-
-        if standered == kind || standered == ColorStandered::None {
-            return match standered {
-                ColorStandered::Cmyk => {
-                    let color = self.to_cmyk().unwrap();
-                    (color.0, color.1, color.2, Some(color.3))
-                }
-                ColorStandered::Rgb => {
-                    let value = self.to_rgb().unwrap();
-                    (value.0, value.1, value.2, None)
-                }
-                ColorStandered::Hsl => {
-                    let value = self.to_hsl().unwrap();
-                    (value.0, value.1, value.2, None)
-                }
-                ColorStandered::Hsv => {
-                    let value = self.to_hsv().unwrap();
-                    (value.0, value.1, value.2, None)
-                }
-                ColorStandered::None => (0., 0., 0., None),
-            };
-        }
-
-        match standered {
-            ColorStandered::Cmyk => match kind {
-                ColorStandered::Hsl => {
-                    let hsl = self.hsl.get().unwrap_or_default();
-                    let rgb = to_rgb::hsl_to_rgb(hsl.0, hsl.1, hsl.2);
-                    let cmyk = from_rgb::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+        other: Handle<Color>,
+        t: NumType,
+        space: ColorStandered,
+        hue_method: HueMethod,
+    ) -> Handle<Color> {
+        self.mix_no_handle(&other, t, space, hue_method).into()
+    }
 
-                    (cmyk.0, cmyk.1, cmyk.2, Some(cmyk.3))
-                }
-                ColorStandered::Hsv => {
-                    let hsv = self.hsv.get().unwrap_or_default();
-                    let rgb = to_rgb::hsv_to_rgb(hsv.0, hsv.1, hsv.2);
-                    let cmyk = from_rgb::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+    fn distance(&self, other: Handle<Color>, metric: DistanceMetric) -> NumType {
+        self.distance_no_handle(&other, metric)
+    }
 
-                    (cmyk.0, cmyk.1, cmyk.2, Some(cmyk.3))
-                }
-                ColorStandered::Rgb => {
-                    let rgb = self.rgb.get().unwrap_or_default();
-                    let cmyk = from_rgb::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+    fn relative_luminance(&self) -> NumType {
+        self.relative_luminance_no_handle()
+    }
 
-                    (cmyk.0, cmyk.1, cmyk.2, Some(cmyk.3))
-                }
-                _ => (0., 0., 0., None),
-            },
-            ColorStandered::Hsl => match kind {
-                ColorStandered::Cmyk => {
-                    let origin = self.cmyk.get().unwrap_or_default();
-                    let rgb = to_rgb::cmyk_to_rgb(origin.0, origin.1, origin.2, origin.3);
-                    let end = from_rgb::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
-
-                    (end.0, end.1, end.2, None)
-                }
-                ColorStandered::Hsv => {
-                    let origin = self.hsv.get().unwrap_or_default();
-                    let rgb = to_rgb::hsv_to_rgb(origin.0, origin.1, origin.2);
-                    let end = from_rgb::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+    fn contrast_ratio(&self, other: Handle<Color>) -> NumType {
+        self.contrast_ratio_no_handle(&other)
+    }
 
-                    (end.0, end.1, end.2, None)
-                }
-                ColorStandered::Rgb => {
-                    let origin = self.rgb.get().unwrap_or_default();
-                    let rgb = origin;
-                    let end = from_rgb::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+    fn wcag_contrast(&self, background: Handle<Color>) -> WcagResult {
+        self.wcag_contrast_no_handle(&background)
+    }
 
-                    (end.0, end.1, end.2, None)
-                }
-                _ => (0., 0., 0., None),
-            },
-            ColorStandered::Hsv => match kind {
-                ColorStandered::Cmyk => {
-                    let origin = self.cmyk.get().unwrap_or_default();
-                    let rgb = to_rgb::cmyk_to_rgb(origin.0, origin.1, origin.2, origin.3);
-                    let end = from_rgb::rgb_to_hsv(rgb.0, rgb.1, rgb.2);
-
-                    (end.0, end.1, end.2, None)
-                }
-                ColorStandered::Hsl => {
-                    let origin = self.hsl.get().unwrap_or_default();
-                    let rgb = to_rgb::hsl_to_rgb(origin.0, origin.1, origin.2);
-                    let end = from_rgb::rgb_to_hsv(rgb.0, rgb.1, rgb.2);
+    fn nearest_name(&self, metric: DistanceMetric, include_xkcd_subset: bool) -> NamedColor {
+        self.nearest_name_no_handle(metric, include_xkcd_subset)
+    }
 
-                    (end.0, end.1, end.2, None)
-                }
-                ColorStandered::Rgb => {
-                    let origin = self.rgb.get().unwrap_or_default();
-                    let rgb = origin;
-                    let end = from_rgb::rgb_to_hsv(rgb.0, rgb.1, rgb.2);
+    fn harmony(
+        &self,
+        harmony: Harmony,
+        space: ColorStandered,
+    ) -> Result<Vec<Handle<Color>>, Exeptions> {
+        self.harmony_no_handle(harmony, space)
+            .map(|colors| colors.into_iter().map(Into::into).collect())
+    }
 
-                    (end.0, end.1, end.2, None)
-                }
-                _ => (0., 0., 0., None),
-            },
-            ColorStandered::Rgb => match kind {
-                ColorStandered::Cmyk => {
-                    let origin = self.cmyk.get().unwrap_or_default();
-                    let rgb = to_rgb::cmyk_to_rgb(origin.0, origin.1, origin.2, origin.3);
-
-                    (rgb.0, rgb.1, rgb.2, None)
-                }
-                ColorStandered::Hsl => {
-                    let origin = self.hsl.get().unwrap_or_default();
-                    let rgb = to_rgb::hsl_to_rgb(origin.0, origin.1, origin.2);
+    fn lighten(&self, amount: NumType, space: AdjustSpace) -> Handle<Color> {
+        self.lighten_no_handle(amount, space).into()
+    }
 
-                    (rgb.0, rgb.1, rgb.2, None)
-                }
-                ColorStandered::Hsv => {
-                    let origin = self.hsv.get().unwrap_or_default();
-                    let rgb = to_rgb::hsv_to_rgb(origin.0, origin.1, origin.2);
+    fn darken(&self, amount: NumType, space: AdjustSpace) -> Handle<Color> {
+        self.darken_no_handle(amount, space).into()
+    }
 
-                    (rgb.0, rgb.1, rgb.2, None)
-                }
-                _ => (0., 0., 0., None),
-            },
-            _ => (0., 0., 0., None),
-        }
+    fn saturate(&self, amount: NumType, space: AdjustSpace) -> Handle<Color> {
+        self.saturate_no_handle(amount, space).into()
     }
 
-    fn to_string(&self, background: Option<Handle<Self>>) -> String {
-        if background.is_none() {
-            let forground = self.into_standered(ColorStandered::Rgb);
-            return format!(
-                "\x1b[38;2;{};{};{}m",
-                forground.0.trunc(),
-                forground.1.trunc(),
-                forground.2.trunc()
-            );
-        }
+    fn desaturate(&self, amount: NumType, space: AdjustSpace) -> Handle<Color> {
+        self.desaturate_no_handle(amount, space).into()
+    }
 
-        let forground = self.into_standered(ColorStandered::Rgb);
-        let background = background.unwrap().into_standered(ColorStandered::Rgb);
-
-        format!(
-            "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
-            forground.0.trunc(),
-            forground.1.trunc(),
-            forground.2.trunc(),
-            background.0.trunc(),
-            background.1.trunc(),
-            background.2.trunc()
-        )
+    fn rotate_hue(&self, degrees: NumType, space: AdjustSpace) -> Handle<Color> {
+        self.rotate_hue_no_handle(degrees, space).into()
     }
 
-    fn into_rgb_with_alpha(&self, to_mix: Handle<Color>, alpha: NumType) -> NewColorResult {
-        if !(0. ..=1.).contains(&alpha) {
-            return Err(Exeptions::AlphaOutOfRange(alpha));
-        }
+    fn grayscale(&self, space: AdjustSpace) -> Handle<Color> {
+        self.grayscale_no_handle(space).into()
+    }
 
-        let fg = self.into_standered(ColorStandered::Rgb);
-        let bg = to_mix.into_standered(ColorStandered::Rgb);
+    fn invert(&self) -> Handle<Color> {
+        self.invert_no_handle().into()
+    }
 
-        let r = ((fg.0 * alpha) + bg.0) / 2.;
-        let g = ((fg.1 * alpha) + bg.1) / 2.;
-        let b = ((fg.2 * alpha) + bg.2) / 2.;
+    fn adjust_temperature(&self, amount: NumType) -> Handle<Color> {
+        self.adjust_temperature_no_handle(amount).into()
+    }
 
-        Color::new(r, g, b)
+    fn monochromatic(
+        &self,
+        kind: MonochromaticKind,
+        steps: u32,
+        space: ColorStandered,
+    ) -> Vec<Handle<Color>> {
+        self.monochromatic_no_handle(kind, steps, space)
+            .into_iter()
+            .map(Into::into)
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::color_print::Color as _;
-    use crate::color_print::ColorStandered;
+    use crate::color_print::{
+        AdjustSpace, AlphaMode, ApcaPolarity, BlendMode, CmcWeights, ColorDepth, ColorStandered,
+        CompositeOperator, DistanceMetric, Exeptions, Harmony, HueMethod, MonochromaticKind,
+        NameSource, WhitePoint,
+    };
     use crate::resourses::Color;
     use crate::utils::{from_rgb, to_rgb};
 
-    fn into(dat: (f64, f64, f64, f64)) -> (f64, f64, f64, Option<f64>) {
-        (dat.0, dat.1, dat.2, Some(dat.3))
+    fn into(dat: (f64, f64, f64, f64)) -> (f64, f64, f64, Option<f64>, f64) {
+        (dat.0, dat.1, dat.2, Some(dat.3), 1.)
     }
 
     #[test]
@@ -459,7 +343,7 @@ mod tests {
         let foreground = Color::new_rgb(10.2, 20.3, 30.4).unwrap();
 
         assert_eq!(
-            foreground.to_string(None),
+            foreground.to_string(None, None, Some(ColorDepth::Truecolor)),
             format!("\x1b[38;2;{};{};{}m", 10, 20, 30)
         )
     }
@@ -470,7 +354,7 @@ mod tests {
         let background = Color::new_rgb(40.2, 50.3, 60.4).unwrap();
 
         assert_eq!(
-            foreground.to_string_no_handle(Some(background)),
+            foreground.to_string_no_handle(Some(background), None, Some(ColorDepth::Truecolor)),
             format!(
                 "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
                 10, 20, 30, 40, 50, 60
@@ -478,6 +362,428 @@ mod tests {
         )
     }
 
+    #[test]
+    fn to_string_depth() {
+        let foreground = Color::new_rgb(250., 10., 10.).unwrap();
+        let background = Color::new_rgb(0., 0., 0.).unwrap();
+
+        assert_eq!(
+            foreground.to_string_no_handle(
+                Some(background.clone()),
+                None,
+                Some(ColorDepth::Xterm256)
+            ),
+            "\x1b[38;5;196m\x1b[48;5;16m"
+        );
+        assert_eq!(
+            foreground.to_string_no_handle(
+                Some(background.clone()),
+                None,
+                Some(ColorDepth::Ansi16)
+            ),
+            "\x1b[91m\x1b[40m"
+        );
+        assert_eq!(
+            foreground.to_string_no_handle(Some(background), None, Some(ColorDepth::None)),
+            ""
+        );
+    }
+
+    #[test]
+    fn to_string_underline() {
+        let foreground = Color::new_rgb(10.2, 20.3, 30.4).unwrap();
+        let underline = Color::new_rgb(250., 10., 10.).unwrap();
+
+        assert_eq!(
+            foreground.to_string_no_handle(
+                None,
+                Some(underline.clone()),
+                Some(ColorDepth::Truecolor)
+            ),
+            "\x1b[38;2;10;20;30m\x1b[58;2;250;10;10m"
+        );
+        assert_eq!(
+            foreground.to_string_no_handle(None, Some(underline), Some(ColorDepth::Xterm256)),
+            "\x1b[38;5;233m\x1b[58;5;196m"
+        );
+    }
+
+    #[test]
+    fn new_css() {
+        let rgb = Color::new_css("rgb(10 20 30)".to_string()).unwrap();
+        let hsl = Color::new_css(" hsl(120deg 50% 25%) ".to_string()).unwrap();
+
+        let translucent = Color::new_css("rgb(10 20 30 / 25%)".to_string()).unwrap();
+
+        assert_eq!(rgb.get_internel_color(), (10., 20., 30., None, 1.));
+        assert_eq!(hsl.get_internel_color(), (120., 0.5, 0.25, None, 1.));
+        assert_eq!(translucent.get_alpha(), 0.25);
+        assert!(Color::new_css("rgb(10 20)".to_string()).is_err());
+    }
+
+    #[test]
+    fn alpha() {
+        let rgb = Color::new_rgb(10.2, 20.3, 30.4).unwrap();
+        let hex = Color::new_hex("#0a141e80".to_string()).unwrap();
+        let translucent = rgb.with_alpha_no_handle(0.5).unwrap();
+
+        assert_eq!(rgb.get_alpha(), 1.);
+        assert_eq!(hex.get_alpha(), 128. / 255.);
+        assert_eq!(
+            translucent.get_internel_color(),
+            (10.2, 20.3, 30.4, None, 0.5)
+        );
+        assert!(rgb.with_alpha_no_handle(1.5).is_err());
+
+        translucent.as_standered(ColorStandered::Hsl);
+        assert_eq!(translucent.get_alpha(), 0.5);
+        assert_eq!(translucent.into_standered(ColorStandered::Cmyk).4, 0.5);
+    }
+
+    #[test]
+    fn blend() {
+        let source = Color::new_rgb(204., 102., 51.).unwrap();
+        let backdrop = Color::new_rgb(51., 153., 229.5).unwrap();
+        let translucent = source.with_alpha_no_handle(0.5).unwrap();
+
+        let opaque = source
+            .blend_no_handle(&backdrop, BlendMode::Darken)
+            .unwrap();
+        let half = translucent
+            .blend_no_handle(&backdrop, BlendMode::Darken)
+            .unwrap();
+
+        assert_eq!(opaque.get_internel_color(), (51., 102., 51., None, 1.));
+        assert_eq!(half.get_internel_color(), (51., 127.5, 140.25, None, 1.));
+    }
+
+    #[test]
+    fn distance() {
+        let rgb = Color::new_rgb(204., 102., 51.).unwrap();
+        let hsl = Color::new_hsl(20., 0.6, 0.5).unwrap();
+        let black = Color::new_rgb(0., 0., 0.).unwrap();
+        let white = Color::new_rgb(255., 255., 255.).unwrap();
+
+        for metric in [
+            DistanceMetric::Cie76,
+            DistanceMetric::Cie94GraphicArts,
+            DistanceMetric::Cie94Textiles,
+            DistanceMetric::Ciede2000,
+            DistanceMetric::Cmc(CmcWeights {
+                lightness: 2.,
+                chroma: 1.,
+            }),
+            DistanceMetric::Itp,
+        ] {
+            assert!(rgb.distance_no_handle(&hsl, metric) < 1e-9);
+            assert!(black.distance_no_handle(&white, metric) > 40.);
+        }
+
+        assert!((black.distance_no_handle(&white, DistanceMetric::Cie76) - 100.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn contrast() {
+        let white = Color::new_rgb(255., 255., 255.).unwrap();
+        let black = Color::new_hsl(0., 0., 0.).unwrap();
+        let translucent = black.with_alpha_no_handle(0.).unwrap();
+
+        assert!((white.contrast_ratio_no_handle(&black) - 21.).abs() < 1e-12);
+        assert!((black.contrast_ratio_no_handle(&white) - 21.).abs() < 1e-12);
+        assert_eq!(translucent.contrast_ratio_no_handle(&white), 1.);
+        assert!(black.wcag_contrast_no_handle(&white).aaa_normal);
+        assert!(!translucent.wcag_contrast_no_handle(&white).aa_large);
+    }
+
+    #[test]
+    fn apca_contrast() {
+        let gray = Color::new_hex("#888".to_string()).unwrap();
+        let white = Color::new_rgb(255., 255., 255.).unwrap();
+
+        let dark_on_light = gray.apca_contrast_no_handle(&white);
+        let light_on_dark = white.apca_contrast_no_handle(&gray);
+
+        assert_eq!(dark_on_light.polarity, ApcaPolarity::DarkOnLight);
+        assert_eq!(light_on_dark.polarity, ApcaPolarity::LightOnDark);
+        assert!((dark_on_light.lc - 63.056469930209424).abs() < 1e-9);
+        assert!((light_on_dark.lc + 68.54146436644962).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mix() {
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+        let blue = Color::new_rgb(0., 0., 255.)
+            .unwrap()
+            .with_alpha_no_handle(0.5)
+            .unwrap();
+
+        let mixed = red.mix_no_handle(&blue, 0.5, ColorStandered::Rgb, HueMethod::Shorter);
+        assert_eq!(mixed.get_internel_color(), (170., 0., 85., None, 0.75));
+
+        let mixed = red.mix_no_handle(&blue, 0.25, ColorStandered::Oklch, HueMethod::Longer);
+        assert_eq!(mixed.get_standered(), ColorStandered::Oklch);
+        assert_eq!(mixed.get_alpha(), 0.875);
+
+        let unchanged = red.mix_no_handle(&blue, -1., ColorStandered::Hsl, HueMethod::Increasing);
+        let rgb = unchanged.rgb_value();
+        assert!((rgb.0 - 255.).abs() < 1e-9 && rgb.1.abs() < 1e-9 && rgb.2.abs() < 1e-9);
+
+        let unchanged = red.mix_no_handle(&blue, f64::NAN, ColorStandered::Hsl, HueMethod::Shorter);
+        let rgb = unchanged.rgb_value();
+        assert!((rgb.0 - 255.).abs() < 1e-9 && rgb.1.abs() < 1e-9 && rgb.2.abs() < 1e-9);
+
+        let black = Color::new_rgb(0., 0., 0.).unwrap();
+        let white = Color::new_rgb(255., 255., 255.).unwrap();
+        let gray = black.mix_no_handle(&white, 0.5, ColorStandered::Cmyk, HueMethod::Shorter);
+        assert_eq!(gray.rgb_value(), (127.5, 127.5, 127.5));
+    }
+
+    #[test]
+    fn names() {
+        let purple = Color::new_name("Rebecca Purple".to_string()).unwrap();
+        assert_eq!(purple.rgb_value(), (102., 51., 153.));
+        assert!(matches!(
+            Color::new_name("blurple".to_string()),
+            Err(Exeptions::UnknownColorName(name)) if name == "blurple"
+        ));
+
+        let near = Color::new_rgb(100., 50., 150.).unwrap();
+        let named = near.nearest_name_no_handle(DistanceMetric::Ciede2000, false);
+        assert_eq!(named.name, "rebeccapurple");
+        assert_eq!(named.source, NameSource::Css);
+
+        let x11 = Color::new_rgb(160., 32., 238.).unwrap();
+        let named = x11.nearest_name_no_handle(DistanceMetric::Cie76, false);
+        assert_eq!(named.name, "purple");
+        assert_eq!(named.source, NameSource::X11);
+
+        let gray = Color::new_name("gray50".to_string()).unwrap();
+        assert_eq!(gray.rgb_value(), (127., 127., 127.));
+        let named = gray.nearest_name_no_handle(DistanceMetric::Ciede2000, false);
+        assert_eq!(named.name, "gray50");
+        assert_eq!(named.source, NameSource::X11);
+
+        let xkcd = Color::new_rgb(4., 116., 150.).unwrap();
+        let named = xkcd.nearest_name_no_handle(DistanceMetric::Ciede2000, true);
+        assert_eq!(named.name, "sea blue");
+        assert_eq!(named.source, NameSource::Xkcd);
+
+        let white = Color::new_rgb(255., 255., 255.).unwrap();
+        let named = white.nearest_name_no_handle(DistanceMetric::Itp, true);
+        assert_eq!(named.name, "white");
+        assert_eq!(named.source, NameSource::Css);
+    }
+
+    fn rounded(color: &Color) -> (f64, f64, f64) {
+        let rgb = color.rgb_value();
+        (rgb.0.round(), rgb.1.round(), rgb.2.round())
+    }
+
+    #[test]
+    fn harmony() {
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+
+        let triadic = red
+            .harmony_no_handle(Harmony::Triadic, ColorStandered::None)
+            .unwrap();
+        let triadic: Vec<_> = triadic.iter().map(rounded).collect();
+        assert_eq!(triadic, [(255., 0., 0.), (0., 255., 0.), (0., 0., 255.)]);
+
+        // A muted color stays in sRGB all the way round, so no gamut mapping moves the hues.
+        let muted = Color::new_rgb(160., 130., 120.).unwrap();
+        let square = muted
+            .with_alpha_no_handle(0.5)
+            .unwrap()
+            .harmony_no_handle(Harmony::Square, ColorStandered::Oklch)
+            .unwrap();
+        let hue = muted.into_standered(ColorStandered::Oklch).2;
+        assert_eq!(square.len(), 4);
+        for (index, color) in square.iter().enumerate() {
+            let expected = (hue + 90. * index as f64).rem_euclid(360.);
+            assert!((color.into_standered(ColorStandered::Oklch).2 - expected).abs() < 1e-9);
+            assert_eq!(color.get_alpha(), 0.5);
+        }
+
+        assert!(matches!(
+            red.harmony_no_handle(Harmony::Complementary, ColorStandered::Lab),
+            Err(Exeptions::StanderedWithoutHue(ColorStandered::Lab))
+        ));
+    }
+
+    #[test]
+    fn harmony_in_gamut() {
+        // Rotating saturated red in OKLCH or LCh leaves sRGB unless it is gamut mapped.
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+
+        for space in [ColorStandered::Oklch, ColorStandered::Lch] {
+            let colors = red.harmony_no_handle(Harmony::Square, space).unwrap();
+
+            for color in colors {
+                let rgb = color.rgb_value();
+                assert_eq!(color.get_standered(), space);
+                assert!(
+                    [rgb.0, rgb.1, rgb.2]
+                        .iter()
+                        .all(|channel| (-1e-9..=255. + 1e-9).contains(channel)),
+                    "{:?} in {:?}",
+                    rgb,
+                    space
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn monochromatic() {
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+
+        let tints = red.monochromatic_no_handle(MonochromaticKind::Tints, 3, ColorStandered::Hsl);
+        let tints: Vec<_> = tints.iter().map(rounded).collect();
+        assert_eq!(
+            tints,
+            [(255., 0., 0.), (227., 113., 113.), (227., 198., 198.)]
+        );
+
+        let shades = red.monochromatic_no_handle(MonochromaticKind::Shades, 2, ColorStandered::Rgb);
+        let shades: Vec<_> = shades.iter().map(rounded).collect();
+        assert_eq!(shades, [(255., 0., 0.), (128., 0., 0.)]);
+
+        let tones = red.monochromatic_no_handle(MonochromaticKind::Tones, 2, ColorStandered::Rgb);
+        assert_eq!(rounded(&tones[1]), (192., 64., 64.));
+
+        assert!(red
+            .monochromatic_no_handle(MonochromaticKind::Tints, 0, ColorStandered::Rgb)
+            .is_empty());
+
+        let black = Color::new_rgb(0., 0., 0.).unwrap();
+        let tints =
+            black.monochromatic_no_handle(MonochromaticKind::Tints, 3, ColorStandered::Cmyk);
+        let tints: Vec<_> = tints.iter().map(rounded).collect();
+        assert_eq!(tints, [(0., 0., 0.), (85., 85., 85.), (170., 170., 170.)]);
+    }
+
+    #[test]
+    fn adjustments() {
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+        let muted = Color::new_hsl(0., 0.5, 0.5).unwrap();
+
+        assert_eq!(
+            rounded(&red.lighten_no_handle(0.2, AdjustSpace::Hsl)),
+            (255., 102., 102.)
+        );
+        assert_eq!(
+            rounded(&red.darken_no_handle(0.2, AdjustSpace::Hsl)),
+            (153., 0., 0.)
+        );
+        assert_eq!(
+            rounded(&red.lighten_no_handle(2., AdjustSpace::Hsl)),
+            (255., 255., 255.)
+        );
+        assert_eq!(
+            rounded(&muted.saturate_no_handle(0.25, AdjustSpace::Hsl)),
+            (223., 32., 32.)
+        );
+        assert_eq!(
+            rounded(&muted.desaturate_no_handle(1., AdjustSpace::Hsl)),
+            (128., 128., 128.)
+        );
+        assert_eq!(
+            rounded(&red.rotate_hue_no_handle(-240., AdjustSpace::Hsl)),
+            (0., 255., 0.)
+        );
+        assert_eq!(
+            rounded(&red.grayscale_no_handle(AdjustSpace::Hsl)),
+            (128., 128., 128.)
+        );
+
+        let oklch = muted.into_standered(ColorStandered::Oklch);
+        let lighter = muted.lighten_no_handle(0.1, AdjustSpace::Oklch);
+        let lighter = lighter.into_standered(ColorStandered::Oklch);
+        assert!((lighter.0 - oklch.0 - 0.1).abs() < 1e-9);
+        assert!((lighter.1 - oklch.1).abs() < 1e-9 && (lighter.2 - oklch.2).abs() < 1e-9);
+
+        let gray = rounded(&red.grayscale_no_handle(AdjustSpace::Oklch));
+        assert!(gray.0 == gray.1 && gray.1 == gray.2 && gray.0 > 128.);
+
+        let translucent = Color::new_rgb(10., 20., 30.)
+            .unwrap()
+            .with_alpha_no_handle(0.5)
+            .unwrap();
+        let inverted = translucent.invert_no_handle();
+        assert_eq!(rounded(&inverted), (245., 235., 225.));
+        assert_eq!(inverted.get_alpha(), 0.5);
+        assert_eq!(translucent.rgb_value(), (10., 20., 30.));
+
+        let gray = Color::new_rgb(128., 128., 128.).unwrap();
+        let warm = rounded(&gray.adjust_temperature_no_handle(0.05));
+        let cool = rounded(&gray.adjust_temperature_no_handle(-0.05));
+        assert!(warm.0 > 128. && warm.2 < 128.);
+        assert!(cool.0 < 128. && cool.2 > 128.);
+    }
+
+    #[test]
+    fn adjustments_in_gamut() {
+        // At the lightness of red plus 0.1 its chroma is far outside sRGB, decoding to a red
+        // channel near 293, so it has to give way.
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+        let oklch = red.into_standered(ColorStandered::Oklch);
+        let lighter = red.lighten_no_handle(0.1, AdjustSpace::Oklch);
+        let rgb = lighter.rgb_value();
+
+        assert_eq!(lighter.get_standered(), ColorStandered::Oklch);
+        assert!([rgb.0, rgb.1, rgb.2]
+            .iter()
+            .all(|channel| (-1e-9..=255. + 1e-9).contains(channel)));
+        let lighter = lighter.into_standered(ColorStandered::Oklch);
+        assert!((lighter.0 - oklch.0 - 0.1).abs() < 0.02);
+        assert!(lighter.1 < oklch.1);
+
+        let warm = red.adjust_temperature_no_handle(0.2).rgb_value();
+        assert!([warm.0, warm.1, warm.2]
+            .iter()
+            .all(|channel| (-1e-9..=255. + 1e-9).contains(channel)));
+
+        let escape = red
+            .lighten_no_handle(0.1, AdjustSpace::Oklch)
+            .to_string_no_handle(None, None, Some(ColorDepth::Truecolor));
+        let channels = escape
+            .trim_start_matches("\x1b[38;2;")
+            .trim_end_matches('m');
+        assert!(
+            channels
+                .split(';')
+                .all(|channel| channel.parse::<u8>().is_ok()),
+            "{:?}",
+            escape
+        );
+    }
+
+    #[test]
+    fn non_finite_adjustments() {
+        let color = Color::new_rgb(160., 130., 120.).unwrap();
+        let rgb = color.rgb_value();
+
+        for amount in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            for space in [AdjustSpace::Hsl, AdjustSpace::Oklch] {
+                for adjusted in [
+                    color.lighten_no_handle(amount, space),
+                    color.darken_no_handle(amount, space),
+                    color.saturate_no_handle(amount, space),
+                    color.desaturate_no_handle(amount, space),
+                    color.rotate_hue_no_handle(amount, space),
+                ] {
+                    let adjusted = adjusted.rgb_value();
+                    assert!((adjusted.0 - rgb.0).abs() < 1e-6, "{:?}", adjusted);
+                    assert!((adjusted.1 - rgb.1).abs() < 1e-6, "{:?}", adjusted);
+                    assert!((adjusted.2 - rgb.2).abs() < 1e-6, "{:?}", adjusted);
+                }
+            }
+
+            let warm = color.adjust_temperature_no_handle(amount).rgb_value();
+            assert!((warm.0 - rgb.0).abs() < 1e-6, "{:?}", warm);
+        }
+    }
+
     #[test]
     fn get_standered() {
         let cymk = Color::new_cmyk(0.1, 0.1, 0.1, 0.1).unwrap();
@@ -491,6 +797,83 @@ mod tests {
         assert_eq!(hsv.get_standered(), ColorStandered::Hsv);
     }
 
+    #[test]
+    fn composite_alpha_modes() {
+        let source = Color::new_rgb(255., 0., 0.)
+            .unwrap()
+            .with_alpha_no_handle(0.5)
+            .unwrap();
+        let backdrop = Color::new_rgb(0., 0., 255.)
+            .unwrap()
+            .with_alpha_no_handle(0.5)
+            .unwrap();
+        let composite = |source: &Color, backdrop: &Color, mode| {
+            source
+                .composite_no_handle(backdrop, CompositeOperator::SrcOver, mode)
+                .unwrap()
+                .get_internel_color()
+        };
+
+        assert_eq!(
+            composite(&source, &backdrop, AlphaMode::Straight),
+            (170., 0., 85., None, 0.75)
+        );
+        assert_eq!(
+            composite(&source, &backdrop, AlphaMode::Premultiplied),
+            (255., 0., 127.5, None, 0.75)
+        );
+
+        // The same colors with their channels premultiplied give the premultiplied form of the
+        // straight result.
+        let premultiplied = |color: &Color| {
+            let (red, green, blue, _, alpha) = color.get_internel_color();
+            Color::new_rgb(red * alpha, green * alpha, blue * alpha)
+                .unwrap()
+                .with_alpha_no_handle(alpha)
+                .unwrap()
+        };
+        assert_eq!(
+            composite(
+                &premultiplied(&source),
+                &premultiplied(&backdrop),
+                AlphaMode::Premultiplied
+            ),
+            (127.5, 0., 63.75, None, 0.75)
+        );
+    }
+
+    #[test]
+    fn value_snapshot() {
+        let color = Color::new_rgb(255., 0., 0.).unwrap();
+        let value = color.value();
+
+        color.as_standered(ColorStandered::Hsl);
+
+        assert_eq!(value.standered(), ColorStandered::Rgb);
+        assert_eq!(value.components(), (255., 0., 0., None));
+        assert_eq!(color.value(), value.into_standered(ColorStandered::Hsl));
+        assert_eq!(
+            Color::from(value).get_internel_color(),
+            (255., 0., 0., None, 1.)
+        );
+    }
+
+    #[test]
+    fn thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Color>();
+
+        let color = std::sync::Arc::new(Color::new_rgb(255., 0., 0.).unwrap());
+        let shared = color.clone();
+        std::thread::spawn(move || shared.as_standered(ColorStandered::Hsl))
+            .join()
+            .unwrap();
+
+        assert_eq!(color.get_standered(), ColorStandered::Hsl);
+        assert_eq!(color.to_hsl(), Some((0., 1., 0.5)));
+        assert_eq!(color.to_rgb(), Some((255., 0., 0.)));
+    }
+
     #[test]
     fn to_standered() {
         let cymk = Color::new_cmyk(0.1, 0.2, 0.3, 0.4).unwrap();
@@ -536,10 +919,10 @@ mod tests {
         let hsl = Color::new_hsl(0.1, 0.2, 0.3).unwrap();
         let hsv = Color::new_hsv(0.1, 0.2, 0.3).unwrap();
 
-        assert_eq!(cymk.get_internel_color(), (0.1, 0.2, 0.3, Some(0.4)));
-        assert_eq!(rgb.get_internel_color(), (10.2, 20.3, 30.4, None));
-        assert_eq!(hsl.get_internel_color(), (0.1, 0.2, 0.3, None));
-        assert_eq!(hsv.get_internel_color(), (0.1, 0.2, 0.3, None));
+        assert_eq!(cymk.get_internel_color(), (0.1, 0.2, 0.3, Some(0.4), 1.));
+        assert_eq!(rgb.get_internel_color(), (10.2, 20.3, 30.4, None, 1.));
+        assert_eq!(hsl.get_internel_color(), (0.1, 0.2, 0.3, None, 1.));
+        assert_eq!(hsv.get_internel_color(), (0.1, 0.2, 0.3, None, 1.));
     }
 
     #[test]
@@ -728,7 +1111,7 @@ mod tests {
         let hsl = hsl.into_standered(standered);
         let hsv = hsv.into_standered(standered);
 
-        assert_eq!(cmyk, (0.1, 0.2, 0.3, Some(0.4)));
+        assert_eq!(cmyk, (0.1, 0.2, 0.3, Some(0.4), 1.));
         assert_eq!(rgb, into(from_rgb::rgb_to_cmyk(10.2, 20.3, 30.4)));
         assert_eq!(
             hsl,
@@ -820,15 +1203,63 @@ mod tests {
         assert_eq!((hsv.0, hsv.1, hsv.2), (0.1, 0.2, 0.3));
     }
 
-    // TODO: Fix this test
+    #[test]
+    fn as_standered_lab() {
+        let rgb = Color::new_rgb(10.2, 20.3, 30.4).unwrap();
+        let d50 = Color::new_lab(50., 10., -20., WhitePoint::D50).unwrap();
+        let d65 = Color::new_lab(50., 10., -20., WhitePoint::D65).unwrap();
+
+        rgb.as_standered(ColorStandered::Lab);
+        assert_eq!(
+            rgb.to_lab().unwrap(),
+            from_rgb::rgb_to_lab(10.2, 20.3, 30.4)
+        );
+
+        rgb.as_standered(ColorStandered::Lch);
+        assert_eq!(
+            rgb.to_lch().unwrap(),
+            from_rgb::rgb_to_lch(10.2, 20.3, 30.4)
+        );
+
+        let lab = d65.into_lab(WhitePoint::D65);
+        assert!((lab.0 - 50.).abs() < 1e-9);
+        assert!((lab.1 - 10.).abs() < 1e-9);
+        assert!((lab.2 + 20.).abs() < 1e-9);
+        assert_ne!(d50.to_lab(), d65.to_lab());
+        assert!(Color::new_lab(101., 0., 0., WhitePoint::D50).is_err());
+        assert!(Color::new_lch(50., -1., 0., WhitePoint::D50).is_err());
+    }
+
+    #[test]
+    fn as_standered_oklab() {
+        let oklab = Color::new_oklab(0.5, 0.1, -0.1).unwrap();
+        let rgb = Color::new_rgb(10.2, 20.3, 30.4).unwrap();
 
-    // #[test]
-    // fn as_rgb_with_alpha() {
-    //     let fore = Color::new_rgb(10.2,20.3,30.4).unwrap();
-    //     let back = Color::new_rgb(40.3,50.4,60.5).unwrap();
+        rgb.as_standered(ColorStandered::Oklab);
+        assert_eq!(
+            rgb.to_oklab().unwrap(),
+            from_rgb::rgb_to_oklab(10.2, 20.3, 30.4)
+        );
+
+        oklab.as_standered(ColorStandered::Oklch);
+        let oklch = oklab.to_oklch().unwrap();
+        assert_eq!(oklab.get_standered(), ColorStandered::Oklch);
+        assert!((oklch.1 - 0.02_f64.sqrt()).abs() < 1e-12);
+        assert!((oklch.2 - 315.).abs() < 1e-9);
+
+        assert!(Color::new_oklab(1.5, 0., 0.).is_err());
+        assert!(Color::new_oklab(0.5, 0.6, 0.).is_err());
+        assert!(Color::new_oklch(0.5, -0.1, 0.).is_err());
+        assert!(Color::new_oklch(0.5, 0.1, 361.).is_err());
+    }
+
+    #[test]
+    fn as_rgb_with_alpha() {
+        let fore = Color::new_rgb(10., 20., 30.).unwrap();
+        let back = Color::new_rgb(40., 50., 60.).unwrap();
 
-    //     let res = fore.as_rgb_with_alpha_no_handle(back, 0.1).unwrap();
+        let res = fore.as_rgb_with_alpha_no_handle(back, 0.5).unwrap();
 
-    //     assert_eq!(res.get_internel_color(), Color::new(20.66, 26.215, 31.77).unwrap().get_internel_color());
-    // }
+        assert_eq!(res.get_internel_color(), (25., 35., 45., None, 1.));
+    }
 }
@@ -1,6 +1,11 @@
-use crate::color_print::{Color as _, NumType};
+use crate::color_print::{
+    Color as _, ColorDepth, ColorStandered, Colormap, Exeptions, HueMethod, NumType, TextRun,
+    WhitePoint,
+};
 use crate::resourses::Color;
 use crate::utils::types::NewColorResult;
+use crate::utils::types::RGB;
+use crate::utils::{ansi, colormap, contrast, gradient, quantize, render, terminal};
 use wai_bindgen_rust::Handle;
 
 pub struct ColorPrint;
@@ -18,23 +23,175 @@ impl crate::color_print::ColorPrint for ColorPrint {
         magenta: NumType,
         yellow: NumType,
         black: NumType,
+        alpha: Option<NumType>,
     ) -> NewColorResult {
-        Color::from_cmyk(cyan, magenta, yellow, black)
+        Color::from_cmyk(cyan, magenta, yellow, black, alpha)
+    }
+
+    fn from_xyz(x: NumType, y: NumType, z: NumType, alpha: Option<NumType>) -> NewColorResult {
+        Color::from_xyz(x, y, z, alpha)
+    }
+
+    fn from_lab(
+        lightness: NumType,
+        a: NumType,
+        b: NumType,
+        white_point: WhitePoint,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_lab(lightness, a, b, white_point, alpha)
+    }
+
+    fn from_lch(
+        lightness: NumType,
+        chroma: NumType,
+        hue: NumType,
+        white_point: WhitePoint,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_lch(lightness, chroma, hue, white_point, alpha)
+    }
+
+    fn from_oklab(
+        lightness: NumType,
+        a: NumType,
+        b: NumType,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_oklab(lightness, a, b, alpha)
+    }
+
+    fn from_oklch(
+        lightness: NumType,
+        chroma: NumType,
+        hue: NumType,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_oklch(lightness, chroma, hue, alpha)
     }
 
     fn from_hex(value: String) -> NewColorResult {
         Color::from_hex(value)
     }
 
-    fn from_hsl(hue: NumType, sateration: NumType, lightness: NumType) -> NewColorResult {
-        Color::from_hsl(hue, sateration, lightness)
+    fn from_css(value: String) -> NewColorResult {
+        Color::from_css(value)
+    }
+
+    fn from_name(name: String) -> NewColorResult {
+        Color::from_name(name)
+    }
+
+    fn from_hsl(
+        hue: NumType,
+        sateration: NumType,
+        lightness: NumType,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_hsl(hue, sateration, lightness, alpha)
+    }
+
+    fn from_hsv(
+        hue: NumType,
+        sateration: NumType,
+        value: NumType,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_hsv(hue, sateration, value, alpha)
+    }
+
+    fn from_rgb(
+        red: NumType,
+        green: NumType,
+        blue: NumType,
+        alpha: Option<NumType>,
+    ) -> NewColorResult {
+        Color::from_rgb(red, green, blue, alpha)
+    }
+
+    fn apca_minimum_lc(font_size: NumType, font_weight: u32) -> Option<NumType> {
+        contrast::apca_minimum_lc(font_size, font_weight)
+    }
+
+    fn detect_color_depth() -> ColorDepth {
+        terminal::detect_depth()
     }
 
-    fn from_hsv(hue: NumType, sateration: NumType, value: NumType) -> NewColorResult {
-        Color::from_hsv(hue, sateration, value)
+    fn parse_ansi(text: String) -> Vec<TextRun> {
+        ansi::parse(&text, &quantize::ANSI_16)
+            .into_iter()
+            .map(|(text, style)| TextRun {
+                text,
+                style: style.into(),
+            })
+            .collect()
     }
 
-    fn from_rgb(red: NumType, green: NumType, blue: NumType) -> NewColorResult {
-        Color::from_rgb(red, green, blue)
+    fn strip_ansi(text: String) -> String {
+        ansi::strip(&text)
     }
+
+    fn display_width(text: String) -> u32 {
+        ansi::display_width(&text) as u32
+    }
+
+    fn gradient(
+        text: String,
+        stops: Vec<Handle<Color>>,
+        space: ColorStandered,
+        hue_method: HueMethod,
+        depth: Option<ColorDepth>,
+    ) -> String {
+        let stops: Vec<Color> = stops.iter().map(|stop| (**stop).clone()).collect();
+
+        gradient::paint_text(&text, &stops, space, hue_method, depth)
+    }
+
+    fn sample_colormap(map: Colormap, value: NumType) -> Handle<Color> {
+        let rgb = colormap::sample(map, value);
+
+        Color::new_standered_unchecked(ColorStandered::Rgb, (rgb.0, rgb.1, rgb.2, None)).into()
+    }
+
+    fn colormap_colors(map: Colormap, count: u32) -> Vec<Handle<Color>> {
+        colormap::colors(map, count as usize)
+            .into_iter()
+            .map(|rgb| {
+                Color::new_standered_unchecked(ColorStandered::Rgb, (rgb.0, rgb.1, rgb.2, None))
+                    .into()
+            })
+            .collect()
+    }
+
+    fn colorbar(map: Colormap, width: u32, depth: Option<ColorDepth>) -> String {
+        colormap::colorbar(map, width as usize, depth)
+    }
+
+    fn ansi_to_html(
+        text: String,
+        palette: Option<Vec<Handle<Color>>>,
+    ) -> Result<String, Exeptions> {
+        let palette = palette_or_default(palette)?;
+
+        Ok(render::to_html(&ansi::parse(&text, &palette), &palette))
+    }
+
+    fn ansi_to_svg(text: String, palette: Option<Vec<Handle<Color>>>) -> Result<String, Exeptions> {
+        let palette = palette_or_default(palette)?;
+
+        Ok(render::to_svg(&ansi::parse(&text, &palette), &palette))
+    }
+}
+
+fn palette_or_default(palette: Option<Vec<Handle<Color>>>) -> Result<[RGB; 16], Exeptions> {
+    let Some(palette) = palette else {
+        return Ok(quantize::ANSI_16);
+    };
+
+    palette
+        .iter()
+        .map(|color| color.rgb_value())
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| Exeptions::IncorrectPaletteLength(palette.len() as u32))
 }
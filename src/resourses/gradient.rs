@@ -0,0 +1,167 @@
+use crate::color_print::{ColorStandered, Easing, Exeptions, GradientStop, HueMethod, NumType};
+use crate::resourses::Color;
+use wai_bindgen_rust::Handle;
+
+#[derive(Clone)]
+pub struct Gradient {
+    /// Colors with their positions from 0 to 1, in ascending order.
+    pub stops: Vec<(Color, NumType)>,
+    pub space: ColorStandered,
+    pub hue_method: HueMethod,
+    pub easing: Easing,
+}
+
+impl crate::color_print::Gradient for Gradient {
+    fn new(
+        stops: Vec<GradientStop>,
+        space: ColorStandered,
+        hue_method: HueMethod,
+    ) -> Result<Handle<Gradient>, Exeptions> {
+        let stops = stops
+            .into_iter()
+            .map(|stop| ((*stop.color).clone(), stop.position))
+            .collect();
+
+        Self::new_no_handle(stops, space, hue_method).map(Into::into)
+    }
+
+    fn from_colors(
+        colors: Vec<Handle<Color>>,
+        space: ColorStandered,
+        hue_method: HueMethod,
+    ) -> Result<Handle<Gradient>, Exeptions> {
+        let colors = colors.iter().map(|color| (**color).clone()).collect();
+
+        Self::from_colors_no_handle(colors, space, hue_method).map(Into::into)
+    }
+
+    fn with_easing(&self, easing: Easing) -> Handle<Gradient> {
+        Self {
+            easing,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn sample(&self, t: NumType) -> Handle<Color> {
+        self.sample_no_handle(t).into()
+    }
+
+    fn colors(&self, count: u32) -> Vec<Handle<Color>> {
+        self.colors_no_handle(count)
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    fn get_stops(&self) -> Vec<GradientStop> {
+        self.stops
+            .iter()
+            .map(|(color, position)| GradientStop {
+                color: color.clone().into(),
+                position: *position,
+            })
+            .collect()
+    }
+
+    fn get_space(&self) -> ColorStandered {
+        self.space
+    }
+
+    fn get_hue_method(&self) -> HueMethod {
+        self.hue_method
+    }
+
+    fn get_easing(&self) -> Easing {
+        self.easing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color_print::Color as _;
+    use crate::color_print::{ColorStandered, Easing, Exeptions, HueMethod};
+    use crate::resourses::{Color, Gradient};
+
+    fn rgb(color: &Color) -> (f64, f64, f64) {
+        let rgb = color.rgb_value();
+        (rgb.0.round(), rgb.1.round(), rgb.2.round())
+    }
+
+    #[test]
+    fn new() {
+        let red = Color::new_rgb(255., 0., 0.).unwrap();
+        let new = |positions: &[f64]| {
+            let stops = positions
+                .iter()
+                .map(|position| (red.clone(), *position))
+                .collect();
+            Gradient::new_no_handle(stops, ColorStandered::Rgb, HueMethod::Shorter).err()
+        };
+
+        assert!(new(&[0., 0.5, 0.5, 1.]).is_none());
+        assert!(matches!(new(&[]), Some(Exeptions::NoGradientStops)));
+        assert!(matches!(
+            new(&[0., 1.5]),
+            Some(Exeptions::StopPositionOutOfRange(1.5))
+        ));
+        assert!(matches!(
+            new(&[0.5, 0.25]),
+            Some(Exeptions::UnorderedStopPosition(0.25))
+        ));
+        assert!(matches!(
+            new(&[f64::NAN]),
+            Some(Exeptions::StopPositionOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn colors() {
+        let gradient = Gradient::from_colors_no_handle(
+            vec![
+                Color::new_rgb(0., 0., 0.).unwrap(),
+                Color::new_rgb(255., 255., 255.).unwrap(),
+            ],
+            ColorStandered::Rgb,
+            HueMethod::Shorter,
+        )
+        .unwrap();
+        let colors: Vec<_> = gradient.colors_no_handle(5).iter().map(rgb).collect();
+
+        assert_eq!(
+            colors,
+            [
+                (0., 0., 0.),
+                (64., 64., 64.),
+                (128., 128., 128.),
+                (191., 191., 191.),
+                (255., 255., 255.)
+            ]
+        );
+        assert_eq!(gradient.colors_no_handle(1).len(), 1);
+        assert!(gradient.colors_no_handle(0).is_empty());
+
+        let eased = Gradient {
+            easing: Easing::EaseIn,
+            ..gradient
+        };
+        assert_eq!(rgb(&eased.sample_no_handle(0.5)), (64., 64., 64.));
+    }
+
+    #[test]
+    fn space() {
+        let gradient = Gradient::from_colors_no_handle(
+            vec![
+                Color::new_rgb(255., 0., 0.).unwrap(),
+                Color::new_rgb(0., 0., 255.).unwrap(),
+            ],
+            ColorStandered::Hsl,
+            HueMethod::Longer,
+        )
+        .unwrap();
+        let sample = gradient.sample_no_handle(0.5);
+
+        assert_eq!(sample.get_standered(), ColorStandered::Hsl);
+        assert_eq!(rgb(&sample), (0., 255., 0.));
+    }
+}
@@ -1,5 +1,9 @@
 mod color;
 mod color_print;
+mod gradient;
+mod style;
 
 pub use color::Color;
 pub use color_print::ColorPrint;
+pub use gradient::Gradient;
+pub use style::Style;
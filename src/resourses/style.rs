@@ -0,0 +1,204 @@
+use crate::color_print::{ColorDepth, TextAttributes, UnderlineStyle};
+use crate::resourses::Color;
+use wai_bindgen_rust::Handle;
+
+#[derive(Clone)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: UnderlineStyle,
+    pub underline_color: Option<Color>,
+    pub blink: bool,
+    pub reverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+}
+
+impl crate::color_print::Style for Style {
+    fn new() -> Handle<Style> {
+        Self::default().into()
+    }
+
+    fn with_foreground(&self, color: Option<Handle<Color>>) -> Handle<Style> {
+        Self {
+            foreground: color.map(|color| (*color).clone()),
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_background(&self, color: Option<Handle<Color>>) -> Handle<Style> {
+        Self {
+            background: color.map(|color| (*color).clone()),
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_bold(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            bold: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_dim(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            dim: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_italic(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            italic: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_underline(&self, underline: UnderlineStyle) -> Handle<Style> {
+        Self {
+            underline,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_underline_color(&self, color: Option<Handle<Color>>) -> Handle<Style> {
+        Self {
+            underline_color: color.map(|color| (*color).clone()),
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_blink(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            blink: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_reverse(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            reverse: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_hidden(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            hidden: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn with_strikethrough(&self, enabled: bool) -> Handle<Style> {
+        Self {
+            strikethrough: enabled,
+            ..self.clone()
+        }
+        .into()
+    }
+
+    fn paint(&self, text: String, depth: Option<ColorDepth>) -> String {
+        self.paint_no_handle(&text, depth)
+    }
+
+    fn get_foreground(&self) -> Option<Handle<Color>> {
+        self.foreground.clone().map(Into::into)
+    }
+
+    fn get_background(&self) -> Option<Handle<Color>> {
+        self.background.clone().map(Into::into)
+    }
+
+    fn get_underline_color(&self) -> Option<Handle<Color>> {
+        self.underline_color.clone().map(Into::into)
+    }
+
+    fn get_attributes(&self) -> TextAttributes {
+        TextAttributes {
+            bold: self.bold,
+            dim: self.dim,
+            italic: self.italic,
+            underline: self.underline,
+            blink: self.blink,
+            reverse: self.reverse,
+            hidden: self.hidden,
+            strikethrough: self.strikethrough,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color_print::{ColorDepth, UnderlineStyle};
+    use crate::resourses::{Color, Style};
+
+    #[test]
+    fn paint() {
+        let style = Style {
+            foreground: Some(Color::new_rgb(255., 0., 0.).unwrap()),
+            bold: true,
+            underline: UnderlineStyle::Curly,
+            ..Default::default()
+        };
+        let squiggly = Style {
+            underline: UnderlineStyle::Curly,
+            underline_color: Some(Color::new_rgb(255., 0., 0.).unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            style.paint_no_handle("error", Some(ColorDepth::Truecolor)),
+            "\x1b[1;4:3;38;2;255;0;0merror\x1b[22;24;39m"
+        );
+        assert_eq!(
+            style.paint_no_handle("error", Some(ColorDepth::Ansi16)),
+            "\x1b[1;4:3;91merror\x1b[22;24;39m"
+        );
+        assert_eq!(
+            style.paint_no_handle("error", Some(ColorDepth::None)),
+            "error"
+        );
+        assert_eq!(
+            squiggly.paint_no_handle("error", Some(ColorDepth::Ansi16)),
+            "\x1b[4:3;58;5;9merror\x1b[24;59m"
+        );
+    }
+
+    #[test]
+    fn paint_attributes() {
+        let style = Style {
+            background: Some(Color::new_rgb(0., 0., 0.).unwrap()),
+            bold: true,
+            dim: true,
+            italic: true,
+            underline: UnderlineStyle::Double,
+            blink: true,
+            reverse: true,
+            hidden: true,
+            strikethrough: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            style.paint_no_handle("text", Some(ColorDepth::Xterm256)),
+            "\x1b[1;2;3;4:2;5;7;8;9;48;5;16mtext\x1b[22;23;24;25;27;28;29;49m"
+        );
+        assert_eq!(
+            Style::default().paint_no_handle("text", Some(ColorDepth::Truecolor)),
+            "text"
+        );
+    }
+}
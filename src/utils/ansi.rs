@@ -0,0 +1,317 @@
+use crate::color_print::UnderlineStyle;
+use crate::utils::quantize::{xterm_256_rgb, ANSI_16};
+use crate::utils::types::*;
+use crate::{Color as ColorStruct, Style as StyleStruct};
+use unicode_width::UnicodeWidthStr;
+
+enum Token<'a> {
+    Text(&'a str),
+    /// The parameters of an SGR sequence, between `\x1b[` and `m`.
+    Sgr(&'a str),
+}
+
+/// Splits `text` into plain text and SGR sequences, dropping any other CSI, OSC or escape
+/// sequence.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((position, character)) = chars.next() {
+        if character != '\x1b' {
+            continue;
+        }
+        if start < position {
+            tokens.push(Token::Text(&text[start..position]));
+        }
+        let mut end = text.len();
+
+        match chars.next() {
+            Some((parameters, '[')) => {
+                let parameters = parameters + 1;
+                for (position, character) in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&character) {
+                        end = position + 1;
+                        if character == 'm' {
+                            tokens.push(Token::Sgr(&text[parameters..position]));
+                        }
+                        break;
+                    }
+                }
+            }
+            Some((_, ']')) => {
+                while let Some((position, character)) = chars.next() {
+                    if character == '\x07' {
+                        end = position + 1;
+                        break;
+                    }
+                    if character == '\x1b' && matches!(chars.peek(), Some((_, '\\'))) {
+                        end = position + 2;
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Intermediate bytes run up to the final byte of the sequence.
+            Some((_, character)) if (' '..='/').contains(&character) => {
+                for (position, character) in chars.by_ref() {
+                    if !(' '..='/').contains(&character) {
+                        end = position + character.len_utf8();
+                        break;
+                    }
+                }
+            }
+            Some((position, character)) => end = position + character.len_utf8(),
+            None => {}
+        }
+
+        start = end;
+    }
+
+    if start < text.len() {
+        tokens.push(Token::Text(&text[start..]));
+    }
+
+    tokens
+}
+
+/// Splits `text` into runs of text sharing the style set by the SGR sequences before them.
+///
+/// Basic and bright colors, along with the first 16 entries of the 256 color palette, are looked
+/// up in `palette`. Other CSI, OSC and escape sequences are dropped.
+pub fn parse(text: &str, palette: &[RGB; 16]) -> Vec<(String, StyleStruct)> {
+    let mut runs = Vec::new();
+    let mut style = StyleStruct::default();
+    let mut current = String::new();
+
+    for token in tokenize(text) {
+        match token {
+            Token::Text(text) => current.push_str(text),
+            Token::Sgr(parameters) => {
+                if !current.is_empty() {
+                    runs.push((std::mem::take(&mut current), style.clone()));
+                }
+                apply(&mut style, parameters, palette);
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        runs.push((current, style));
+    }
+
+    runs
+}
+
+/// Removes every escape sequence from `text`, including the ones `to_string` and `paint` emit.
+pub fn strip(text: &str) -> String {
+    tokenize(text)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            Token::Sgr(_) => None,
+        })
+        .collect()
+}
+
+/// The number of terminal columns `text` takes up once escape sequences are removed, counting
+/// East Asian wide and fullwidth characters as two columns.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(strip(text).as_str())
+}
+
+fn color(rgb: RGB) -> Option<ColorStruct> {
+    ColorStruct::new_rgb(rgb.0, rgb.1, rgb.2).ok()
+}
+
+fn indexed(index: u16, palette: &[RGB; 16]) -> Option<ColorStruct> {
+    match index {
+        0..=15 => color(palette[index as usize]),
+        16..=255 => color(xterm_256_rgb(index as u8)),
+        _ => None,
+    }
+}
+
+/// Reads an extended color from `38`, `48` or `58` style parameters.
+///
+/// `sub` holds the colon separated parts after the code. When it is empty, the color is instead
+/// taken from the following semicolon separated parameters, which are consumed.
+fn extended<'a>(
+    sub: &[&str],
+    rest: &mut impl Iterator<Item = &'a str>,
+    palette: &[RGB; 16],
+) -> Option<ColorStruct> {
+    let number = |value: &str| value.parse::<u16>().ok();
+
+    if sub.is_empty() {
+        return match rest.next().and_then(number)? {
+            5 => indexed(rest.next().and_then(number)?, palette),
+            2 => {
+                let red = rest.next().and_then(number)?;
+                let green = rest.next().and_then(number)?;
+                let blue = rest.next().and_then(number)?;
+                color((red as f64, green as f64, blue as f64))
+            }
+            _ => None,
+        };
+    }
+
+    match (number(sub[0])?, &sub[1..]) {
+        (5, [index, ..]) => indexed(number(index)?, palette),
+        // The color space id before the channels is optional.
+        (2, [_, red, green, blue, ..]) | (2, [red, green, blue]) => color((
+            number(red)? as f64,
+            number(green)? as f64,
+            number(blue)? as f64,
+        )),
+        _ => None,
+    }
+}
+
+fn apply(style: &mut StyleStruct, parameters: &str, palette: &[RGB; 16]) {
+    let mut parameters = parameters.split(';');
+
+    while let Some(parameter) = parameters.next() {
+        let mut parts = parameter.split(':');
+        let code = parts.next().unwrap_or_default();
+        let code = if code.is_empty() {
+            0
+        } else {
+            match code.parse::<u16>() {
+                Ok(code) => code,
+                Err(_) => continue,
+            }
+        };
+        let sub: Vec<&str> = parts.collect();
+
+        match code {
+            0 => *style = StyleStruct::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => {
+                style.underline = match sub.first().copied() {
+                    Some("0") => UnderlineStyle::None,
+                    Some("2") => UnderlineStyle::Double,
+                    Some("3") => UnderlineStyle::Curly,
+                    Some("4") => UnderlineStyle::Dotted,
+                    Some("5") => UnderlineStyle::Dashed,
+                    _ => UnderlineStyle::Single,
+                }
+            }
+            5 | 6 => style.blink = true,
+            7 => style.reverse = true,
+            8 => style.hidden = true,
+            9 => style.strikethrough = true,
+            21 => style.underline = UnderlineStyle::Double,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = UnderlineStyle::None,
+            25 => style.blink = false,
+            27 => style.reverse = false,
+            28 => style.hidden = false,
+            29 => style.strikethrough = false,
+            30..=37 => style.foreground = indexed(code - 30, palette),
+            38 => style.foreground = extended(&sub, &mut parameters, palette),
+            39 => style.foreground = None,
+            40..=47 => style.background = indexed(code - 40, palette),
+            48 => style.background = extended(&sub, &mut parameters, palette),
+            49 => style.background = None,
+            58 => style.underline_color = extended(&sub, &mut parameters, palette),
+            59 => style.underline_color = None,
+            90..=97 => style.foreground = indexed(code - 90 + 8, palette),
+            100..=107 => style.background = indexed(code - 100 + 8, palette),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(color: &Option<ColorStruct>) -> Option<RGB> {
+        color.as_ref().map(ColorStruct::rgb_value)
+    }
+
+    #[test]
+    fn runs() {
+        let runs = parse(
+            "plain\x1b[1;31mred\x1b[22;48;5;196m\x1b[Kon red\x1b[0m done",
+            &ANSI_16,
+        );
+        let texts: Vec<&str> = runs.iter().map(|(text, _)| text.as_str()).collect();
+
+        assert_eq!(texts, ["plain", "red", "on red", " done"]);
+        assert!(runs[0].1.foreground.is_none() && !runs[0].1.bold);
+        assert!(runs[1].1.bold);
+        assert_eq!(rgb(&runs[1].1.foreground), Some((205., 0., 0.)));
+        assert!(!runs[2].1.bold);
+        assert_eq!(rgb(&runs[2].1.foreground), Some((205., 0., 0.)));
+        assert_eq!(rgb(&runs[2].1.background), Some((255., 0., 0.)));
+        assert!(runs[3].1.foreground.is_none() && runs[3].1.background.is_none());
+    }
+
+    #[test]
+    fn extended_colors() {
+        let runs = parse(
+            "\x1b[38;2;10;20;30;48:2::40:50:60;58:5:9;4:3;93ma\x1b[38:2:1:2:3;59;24mb",
+            &ANSI_16,
+        );
+
+        assert_eq!(rgb(&runs[0].1.foreground), Some((255., 255., 0.)));
+        assert_eq!(rgb(&runs[0].1.background), Some((40., 50., 60.)));
+        assert_eq!(rgb(&runs[0].1.underline_color), Some((255., 0., 0.)));
+        assert_eq!(runs[0].1.underline, UnderlineStyle::Curly);
+
+        assert_eq!(rgb(&runs[1].1.foreground), Some((1., 2., 3.)));
+        assert_eq!(rgb(&runs[1].1.background), Some((40., 50., 60.)));
+        assert!(runs[1].1.underline_color.is_none());
+        assert_eq!(runs[1].1.underline, UnderlineStyle::None);
+    }
+
+    #[test]
+    fn other_sequences() {
+        let runs = parse(
+            "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07\x1b[2J\x1b(Bmore",
+            &ANSI_16,
+        );
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].0, "linkmore");
+    }
+
+    #[test]
+    fn strip_and_width() {
+        let color = ColorStruct::new_rgb(10., 20., 30.).unwrap();
+        let painted = format!(
+            "{}表格\x1b[0m ok\x1b]0;title\x07",
+            color.to_string_no_handle(
+                Some(color.clone()),
+                Some(color.clone()),
+                Some(crate::color_print::ColorDepth::Truecolor)
+            )
+        );
+
+        assert_eq!(strip(&painted), "表格 ok");
+        assert_eq!(display_width(&painted), 7);
+        assert_eq!(strip("a\x1b(Bb\x1b[2Kc\x1b"), "abc");
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("ｆｕｌｌ"), 8);
+    }
+
+    #[test]
+    fn round_trip() {
+        let color = ColorStruct::new_rgb(10., 20., 30.).unwrap();
+        let text = format!(
+            "{}text",
+            color.to_string_no_handle(None, None, Some(crate::color_print::ColorDepth::Truecolor))
+        );
+        let runs = parse(&text, &ANSI_16);
+
+        assert_eq!(rgb(&runs[0].1.foreground), Some((10., 20., 30.)));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::color_print::{BlendMode, NumType};
+use crate::utils::types::*;
+
+fn multiply(backdrop: NumType, source: NumType) -> NumType {
+    backdrop * source
+}
+
+fn screen(backdrop: NumType, source: NumType) -> NumType {
+    backdrop + source - backdrop * source
+}
+
+fn hard_light(backdrop: NumType, source: NumType) -> NumType {
+    if source <= 0.5 {
+        multiply(backdrop, 2. * source)
+    } else {
+        screen(backdrop, 2. * source - 1.)
+    }
+}
+
+fn color_dodge(backdrop: NumType, source: NumType) -> NumType {
+    if backdrop == 0. {
+        0.
+    } else if source >= 1. {
+        1.
+    } else {
+        (backdrop / (1. - source)).min(1.)
+    }
+}
+
+fn color_burn(backdrop: NumType, source: NumType) -> NumType {
+    if backdrop >= 1. {
+        1.
+    } else if source == 0. {
+        0.
+    } else {
+        1. - ((1. - backdrop) / source).min(1.)
+    }
+}
+
+fn soft_light(backdrop: NumType, source: NumType) -> NumType {
+    if source <= 0.5 {
+        backdrop - (1. - 2. * source) * backdrop * (1. - backdrop)
+    } else {
+        let d = if backdrop <= 0.25 {
+            ((16. * backdrop - 12.) * backdrop + 4.) * backdrop
+        } else {
+            backdrop.sqrt()
+        };
+
+        backdrop + (2. * source - 1.) * (d - backdrop)
+    }
+}
+
+fn separable(backdrop: NumType, source: NumType, mode: BlendMode) -> NumType {
+    match mode {
+        BlendMode::Multiply => multiply(backdrop, source),
+        BlendMode::Screen => screen(backdrop, source),
+        BlendMode::Overlay => hard_light(source, backdrop),
+        BlendMode::Darken => backdrop.min(source),
+        BlendMode::Lighten => backdrop.max(source),
+        BlendMode::ColorDodge => color_dodge(backdrop, source),
+        BlendMode::ColorBurn => color_burn(backdrop, source),
+        BlendMode::HardLight => hard_light(backdrop, source),
+        BlendMode::SoftLight => soft_light(backdrop, source),
+        BlendMode::Difference => (backdrop - source).abs(),
+        BlendMode::Exclusion => backdrop + source - 2. * backdrop * source,
+        _ => source,
+    }
+}
+
+fn lum(color: [NumType; 3]) -> NumType {
+    0.3 * color[0] + 0.59 * color[1] + 0.11 * color[2]
+}
+
+fn clip_color(color: [NumType; 3]) -> [NumType; 3] {
+    let l = lum(color);
+    let n = color[0].min(color[1]).min(color[2]);
+    let x = color[0].max(color[1]).max(color[2]);
+
+    color.map(|c| {
+        let c = if n < 0. { l + (c - l) * l / (l - n) } else { c };
+
+        if x > 1. {
+            l + (c - l) * (1. - l) / (x - l)
+        } else {
+            c
+        }
+    })
+}
+
+fn set_lum(color: [NumType; 3], l: NumType) -> [NumType; 3] {
+    let d = l - lum(color);
+
+    clip_color(color.map(|c| c + d))
+}
+
+fn sat(color: [NumType; 3]) -> NumType {
+    color[0].max(color[1]).max(color[2]) - color[0].min(color[1]).min(color[2])
+}
+
+fn set_sat(color: [NumType; 3], s: NumType) -> [NumType; 3] {
+    let mut order = [0, 1, 2];
+    order.sort_by(|a, b| color[*a].total_cmp(&color[*b]));
+    let [min, mid, max] = order;
+
+    let mut result = [0.; 3];
+    if color[max] > color[min] {
+        result[mid] = (color[mid] - color[min]) * s / (color[max] - color[min]);
+        result[max] = s;
+    }
+
+    result
+}
+
+/// Blends `source` over `backdrop` per the W3C Compositing and Blending spec, ignoring alpha.
+///
+/// Channels are in `0..=255`.
+pub fn blend(source: RGB, backdrop: RGB, mode: BlendMode) -> RGB {
+    let cs = [source.0 / 255., source.1 / 255., source.2 / 255.];
+    let cb = [backdrop.0 / 255., backdrop.1 / 255., backdrop.2 / 255.];
+
+    let result = match mode {
+        BlendMode::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+        BlendMode::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+        BlendMode::Color => set_lum(cs, lum(cb)),
+        BlendMode::Luminosity => set_lum(cb, lum(cs)),
+        mode => [
+            separable(cb[0], cs[0], mode),
+            separable(cb[1], cs[1], mode),
+            separable(cb[2], cs[2], mode),
+        ],
+    };
+
+    (result[0] * 255., result[1] * 255., result[2] * 255.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: RGB = (204., 102., 51.);
+    const BACKDROP: RGB = (51., 153., 229.5);
+
+    #[test]
+    fn blend_modes() {
+        // Reference results for source (0.8, 0.4, 0.2) over backdrop (0.2, 0.6, 0.9), computed
+        // with a separate JavaScript transcription of the section 10 pseudocode in the W3C
+        // Compositing and Blending Level 1 recommendation rather than with this module.
+        let matrix = [
+            (BlendMode::Normal, (0.8, 0.4, 0.2)),
+            (BlendMode::Multiply, (0.16, 0.24, 0.18)),
+            (BlendMode::Screen, (0.84, 0.76, 0.92)),
+            (BlendMode::Overlay, (0.32, 0.52, 0.84)),
+            (BlendMode::Darken, (0.2, 0.4, 0.2)),
+            (BlendMode::Lighten, (0.8, 0.6, 0.9)),
+            (BlendMode::ColorDodge, (1., 1., 1.)),
+            (BlendMode::ColorBurn, (0., 0., 0.5)),
+            (BlendMode::HardLight, (0.68, 0.48, 0.36)),
+            (BlendMode::SoftLight, (0.3488, 0.552, 0.846)),
+            (BlendMode::Difference, (0.6, 0.2, 0.7)),
+            (BlendMode::Exclusion, (0.68, 0.52, 0.74)),
+            (
+                BlendMode::Hue,
+                (0.865_333_333_3, 0.398_666_666_7, 0.165_333_333_3),
+            ),
+            (
+                BlendMode::Saturation,
+                (0.244_714_285_7, 0.587_571_428_6, 0.844_714_285_7),
+            ),
+            (BlendMode::Color, (0.815, 0.415, 0.215)),
+            (BlendMode::Luminosity, (0.185, 0.585, 0.885)),
+        ];
+
+        for (mode, expected) in matrix {
+            let result = blend(SOURCE, BACKDROP, mode);
+            let expected = (expected.0 * 255., expected.1 * 255., expected.2 * 255.);
+
+            assert!(
+                (result.0 - expected.0).abs() <= 0.00001
+                    && (result.1 - expected.1).abs() <= 0.00001
+                    && (result.2 - expected.2).abs() <= 0.00001,
+                "{:?} gave {:?} instead of {:?}",
+                mode,
+                result,
+                expected
+            );
+        }
+    }
+
+    fn assert_close(result: RGB, expected: RGB) {
+        assert!(
+            (result.0 - expected.0).abs() <= 0.00001
+                && (result.1 - expected.1).abs() <= 0.00001
+                && (result.2 - expected.2).abs() <= 0.00001,
+            "{:?} instead of {:?}",
+            result,
+            expected
+        );
+    }
+
+    #[test]
+    fn specification_properties() {
+        // The behavior the W3C Compositing and Blending Level 1 recommendation describes for each
+        // mode in section 10, checked against a few backdrops.
+        const BLACK: RGB = (0., 0., 0.);
+        const WHITE: RGB = (255., 255., 255.);
+        const GRAY: RGB = (127.5, 127.5, 127.5);
+
+        for backdrop in [BACKDROP, SOURCE, GRAY, (255., 0., 127.5)] {
+            let inverted = (255. - backdrop.0, 255. - backdrop.1, 255. - backdrop.2);
+
+            // "Multiplying any color with black results in black. Multiplying any color with
+            // white preserves the original color."
+            assert_close(blend(BLACK, backdrop, BlendMode::Multiply), BLACK);
+            assert_close(blend(WHITE, backdrop, BlendMode::Multiply), backdrop);
+            // "Screening with white produces white; screening with black leaves the color
+            // unchanged."
+            assert_close(blend(WHITE, backdrop, BlendMode::Screen), WHITE);
+            assert_close(blend(BLACK, backdrop, BlendMode::Screen), backdrop);
+            // Color dodge: "Painting with black produces no change." Color burn: "Painting with
+            // white produces no change."
+            assert_close(blend(BLACK, backdrop, BlendMode::ColorDodge), backdrop);
+            assert_close(blend(WHITE, backdrop, BlendMode::ColorBurn), backdrop);
+            // Difference and exclusion: "Painting with white inverts the backdrop color;
+            // painting with black produces no change."
+            assert_close(blend(WHITE, backdrop, BlendMode::Difference), inverted);
+            assert_close(blend(BLACK, backdrop, BlendMode::Difference), backdrop);
+            assert_close(blend(WHITE, backdrop, BlendMode::Exclusion), inverted);
+            assert_close(blend(BLACK, backdrop, BlendMode::Exclusion), backdrop);
+            // Darken and lighten select the darker and lighter of the two.
+            assert_close(blend(WHITE, backdrop, BlendMode::Darken), backdrop);
+            assert_close(blend(BLACK, backdrop, BlendMode::Lighten), backdrop);
+            // Hard light with 50% gray is neutral, as is overlay, which is hard light with the
+            // layers swapped.
+            assert_close(blend(GRAY, backdrop, BlendMode::HardLight), backdrop);
+            assert_close(blend(backdrop, GRAY, BlendMode::Overlay), backdrop);
+            // Soft light with 50% gray is neutral too, since it darkens or lightens depending on
+            // which side of gray the source falls.
+            assert_close(blend(GRAY, backdrop, BlendMode::SoftLight), backdrop);
+        }
+
+        // Soft light: "Painting with pure black or white produces a distinctly darker or lighter
+        // area, but does not result in pure black or white."
+        let darker = blend(BLACK, GRAY, BlendMode::SoftLight);
+        let lighter = blend(WHITE, GRAY, BlendMode::SoftLight);
+        assert!(darker.0 > 0. && darker.0 < GRAY.0);
+        assert!(lighter.0 > GRAY.0 && lighter.0 < 255.);
+
+        // Luminosity and color swap the roles of the two layers, and a gray source has no hue or
+        // saturation to give.
+        assert_close(
+            blend(SOURCE, BACKDROP, BlendMode::Luminosity),
+            blend(BACKDROP, SOURCE, BlendMode::Color),
+        );
+        assert_close(blend(GRAY, GRAY, BlendMode::Hue), GRAY);
+        assert_close(blend(GRAY, GRAY, BlendMode::Saturation), GRAY);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::color_print::{AlphaMode, CompositeOperator, NumType};
+use crate::utils::types::*;
+
+/// The Porter-Duff fractions `(Fa, Fb)` applied to the source and backdrop.
+fn fractions(
+    operator: CompositeOperator,
+    source_alpha: NumType,
+    backdrop_alpha: NumType,
+) -> (NumType, NumType) {
+    match operator {
+        CompositeOperator::Clear => (0., 0.),
+        CompositeOperator::Src => (1., 0.),
+        CompositeOperator::Dst => (0., 1.),
+        CompositeOperator::SrcOver => (1., 1. - source_alpha),
+        CompositeOperator::DstOver => (1. - backdrop_alpha, 1.),
+        CompositeOperator::SrcIn => (backdrop_alpha, 0.),
+        CompositeOperator::DstIn => (0., source_alpha),
+        CompositeOperator::SrcOut => (1. - backdrop_alpha, 0.),
+        CompositeOperator::DstOut => (0., 1. - source_alpha),
+        CompositeOperator::SrcAtop => (backdrop_alpha, 1. - source_alpha),
+        CompositeOperator::DstAtop => (1. - backdrop_alpha, source_alpha),
+        CompositeOperator::Xor => (1. - backdrop_alpha, 1. - source_alpha),
+    }
+}
+
+fn premultiply(rgb: RGB, alpha: NumType) -> RGB {
+    (rgb.0 * alpha, rgb.1 * alpha, rgb.2 * alpha)
+}
+
+fn unpremultiply(rgb: RGB, alpha: NumType) -> RGB {
+    if alpha == 0. {
+        return (0., 0., 0.);
+    }
+
+    (rgb.0 / alpha, rgb.1 / alpha, rgb.2 / alpha)
+}
+
+/// Composites `source` onto `backdrop` with the given Porter-Duff operator.
+///
+/// Channels are in `0..=255` and alphas in `0..=1`. With `AlphaMode::Premultiplied` the
+/// channels of both inputs are expected to already be multiplied by their alpha, and the
+/// result is returned premultiplied as well.
+pub fn composite(
+    (source, source_alpha): (RGB, NumType),
+    (backdrop, backdrop_alpha): (RGB, NumType),
+    operator: CompositeOperator,
+    mode: AlphaMode,
+) -> (RGB, NumType) {
+    let (source, backdrop) = match mode {
+        AlphaMode::Straight => (
+            premultiply(source, source_alpha),
+            premultiply(backdrop, backdrop_alpha),
+        ),
+        AlphaMode::Premultiplied => (source, backdrop),
+    };
+
+    let (fa, fb) = fractions(operator, source_alpha, backdrop_alpha);
+    let alpha = source_alpha * fa + backdrop_alpha * fb;
+    let rgb = (
+        source.0 * fa + backdrop.0 * fb,
+        source.1 * fa + backdrop.1 * fb,
+        source.2 * fa + backdrop.2 * fb,
+    );
+
+    match mode {
+        AlphaMode::Straight => (unpremultiply(rgb, alpha), alpha),
+        AlphaMode::Premultiplied => (rgb, alpha),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: (RGB, NumType) = ((255., 0., 0.), 0.5);
+    const BACKDROP: (RGB, NumType) = ((0., 0., 255.), 0.5);
+
+    fn straight(operator: CompositeOperator) -> (RGB, NumType) {
+        composite(SOURCE, BACKDROP, operator, AlphaMode::Straight)
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(straight(CompositeOperator::Clear), ((0., 0., 0.), 0.));
+        assert_eq!(straight(CompositeOperator::Src), SOURCE);
+        assert_eq!(straight(CompositeOperator::Dst), BACKDROP);
+        assert_eq!(
+            straight(CompositeOperator::SrcOver),
+            ((170., 0., 85.), 0.75)
+        );
+        assert_eq!(
+            straight(CompositeOperator::DstOver),
+            ((85., 0., 170.), 0.75)
+        );
+        assert_eq!(straight(CompositeOperator::SrcIn), ((255., 0., 0.), 0.25));
+        assert_eq!(straight(CompositeOperator::DstIn), ((0., 0., 255.), 0.25));
+        assert_eq!(straight(CompositeOperator::SrcOut), ((255., 0., 0.), 0.25));
+        assert_eq!(straight(CompositeOperator::DstOut), ((0., 0., 255.), 0.25));
+        assert_eq!(
+            straight(CompositeOperator::SrcAtop),
+            ((127.5, 0., 127.5), 0.5)
+        );
+        assert_eq!(
+            straight(CompositeOperator::DstAtop),
+            ((127.5, 0., 127.5), 0.5)
+        );
+        assert_eq!(straight(CompositeOperator::Xor), ((127.5, 0., 127.5), 0.5));
+    }
+
+    #[test]
+    fn opaque_backdrop() {
+        let (rgb, alpha) = composite(
+            ((255., 255., 255.), 0.25),
+            ((0., 0., 0.), 1.),
+            CompositeOperator::SrcOver,
+            AlphaMode::Straight,
+        );
+
+        assert_eq!((rgb, alpha), ((63.75, 63.75, 63.75), 1.));
+    }
+
+    #[test]
+    fn premultiplied() {
+        let (rgb, alpha) = composite(
+            ((127.5, 0., 0.), 0.5),
+            ((0., 0., 127.5), 0.5),
+            CompositeOperator::SrcOver,
+            AlphaMode::Premultiplied,
+        );
+
+        assert_eq!((rgb, alpha), ((127.5, 0., 63.75), 0.75));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::color_print::{ApcaPolarity, ApcaResult, NumType, WcagResult};
+use crate::utils::helper::*;
+use crate::utils::types::*;
+
+/// The WCAG 2.x relative luminance of an sRGB color with channels in `0..=255`.
+pub fn relative_luminance(rgb: RGB) -> NumType {
+    0.2126 * srgb_to_linear(rgb.0 / 255.)
+        + 0.7152 * srgb_to_linear(rgb.1 / 255.)
+        + 0.0722 * srgb_to_linear(rgb.2 / 255.)
+}
+
+/// The WCAG 2.x contrast ratio between two relative luminances, from `1` to `21`.
+pub fn contrast_ratio(luminance1: NumType, luminance2: NumType) -> NumType {
+    let lighter = luminance1.max(luminance2);
+    let darker = luminance1.min(luminance2);
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Checks a contrast ratio against the AA and AAA thresholds for normal and large text.
+pub fn wcag(ratio: NumType) -> WcagResult {
+    WcagResult {
+        ratio,
+        aa_normal: ratio >= 4.5,
+        aa_large: ratio >= 3.,
+        aaa_normal: ratio >= 7.,
+        aaa_large: ratio >= 4.5,
+    }
+}
+
+/// The APCA screen luminance of an sRGB color with channels in `0..=255`.
+///
+/// APCA uses a plain 2.4 gamma rather than the piecewise sRGB curve.
+pub fn apca_luminance(rgb: RGB) -> NumType {
+    0.2126729 * (rgb.0 / 255.).powf(2.4)
+        + 0.7151522 * (rgb.1 / 255.).powf(2.4)
+        + 0.072175 * (rgb.2 / 255.).powf(2.4)
+}
+
+/// APCA 0.0.98G lightness contrast (Lc) of `text` over `background`, given as APCA luminances.
+///
+/// Lc is positive for dark text on a light background and negative for light text on a dark one.
+pub fn apca_contrast(text: NumType, background: NumType) -> ApcaResult {
+    let clamp = |y: NumType| {
+        if y > 0.022 {
+            y
+        } else {
+            y + (0.022 - y).powf(1.414)
+        }
+    };
+    let text = clamp(text.max(0.));
+    let background = clamp(background.max(0.));
+
+    let polarity = if background > text {
+        ApcaPolarity::DarkOnLight
+    } else {
+        ApcaPolarity::LightOnDark
+    };
+
+    if (background - text).abs() < 0.0005 {
+        return ApcaResult { lc: 0., polarity };
+    }
+
+    let lc = match polarity {
+        ApcaPolarity::DarkOnLight => {
+            let sapc = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+            if sapc < 0.1 {
+                0.
+            } else {
+                sapc - 0.027
+            }
+        }
+        ApcaPolarity::LightOnDark => {
+            let sapc = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+            if sapc > -0.1 {
+                0.
+            } else {
+                sapc + 0.027
+            }
+        }
+    };
+
+    ApcaResult {
+        lc: lc * 100.,
+        polarity,
+    }
+}
+
+/// The minimum absolute Lc recommended for text of `font_size` pixels at `font_weight`, following
+/// the APCA Bronze simple mode guidance. Returns `None` when the text is too small or thin for any
+/// level.
+pub fn apca_minimum_lc(font_size: NumType, font_weight: u32) -> Option<NumType> {
+    // Each level lists the (size, weight) pairs that are readable at that contrast.
+    const LEVELS: [(NumType, &[(NumType, u32)]); 4] = [
+        (45., &[(36., 400), (24., 700)]),
+        (
+            60.,
+            &[
+                (48., 200),
+                (36., 300),
+                (24., 400),
+                (21., 500),
+                (18., 600),
+                (16., 700),
+            ],
+        ),
+        (75., &[(24., 300), (18., 400), (16., 500), (14., 700)]),
+        (90., &[(18., 300), (14., 400)]),
+    ];
+
+    LEVELS.iter().find_map(|(lc, fonts)| {
+        fonts
+            .iter()
+            .any(|(size, weight)| font_size >= *size && font_weight >= *weight)
+            .then_some(*lc)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn luminance() {
+        assert_eq!(relative_luminance((0., 0., 0.)), 0.);
+        assert!((relative_luminance((255., 255., 255.)) - 1.).abs() < 1e-12);
+        assert!((relative_luminance((255., 0., 0.)) - 0.2126).abs() < 1e-12);
+        assert!((relative_luminance((119., 119., 119.)) - 0.184474994).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ratios() {
+        let white = relative_luminance((255., 255., 255.));
+        let black = relative_luminance((0., 0., 0.));
+        let gray = relative_luminance((119., 119., 119.));
+
+        assert!((contrast_ratio(white, black) - 21.).abs() < 1e-12);
+        assert_eq!(contrast_ratio(black, white), contrast_ratio(white, black));
+        assert!((contrast_ratio(white, gray) - 4.478).abs() < 0.001);
+
+        let result = wcag(contrast_ratio(white, gray));
+        assert!(!result.aa_normal && result.aa_large && !result.aaa_normal && !result.aaa_large);
+
+        let result = wcag(contrast_ratio(white, black));
+        assert!(result.aa_normal && result.aa_large && result.aaa_normal && result.aaa_large);
+    }
+
+    fn apca(text: RGB, background: RGB) -> NumType {
+        apca_contrast(apca_luminance(text), apca_luminance(background)).lc
+    }
+
+    #[test]
+    fn apca_reference() {
+        // Reference values from the apca-w3 0.0.98G test suite.
+        assert!((apca((136., 136., 136.), (255., 255., 255.)) - 63.056469930209424).abs() < 1e-9);
+        assert!((apca((255., 255., 255.), (136., 136., 136.)) + 68.54146436644962).abs() < 1e-9);
+        assert!((apca((0., 0., 0.), (170., 170., 170.)) - 58.146262578561334).abs() < 1e-9);
+        assert!((apca((170., 170., 170.), (0., 0., 0.)) + 56.24113336839742).abs() < 1e-9);
+        assert_eq!(apca((119., 119., 119.), (119., 119., 119.)), 0.);
+    }
+
+    #[test]
+    fn apca_fonts() {
+        assert_eq!(apca_minimum_lc(16., 400), Some(90.));
+        assert_eq!(apca_minimum_lc(18., 400), Some(75.));
+        assert_eq!(apca_minimum_lc(24., 400), Some(60.));
+        assert_eq!(apca_minimum_lc(24., 700), Some(45.));
+        assert_eq!(apca_minimum_lc(12., 900), None);
+        assert_eq!(apca_minimum_lc(72., 100), None);
+    }
+}
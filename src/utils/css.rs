@@ -0,0 +1,612 @@
+use crate::color_print::{CssError, CssErrorKind, Exeptions, NumType};
+use crate::utils::types::*;
+use crate::utils::{names, to_rgb};
+
+pub enum CssColor {
+    Rgb(RGB),
+    Hsl(HSL),
+}
+
+pub struct ParsedCss {
+    pub color: CssColor,
+    pub alpha: NumType,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Function(String),
+    Number(NumType),
+    Percentage(NumType),
+    Dimension(NumType, String),
+    Comma,
+    Slash,
+    Close,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Component {
+    Number(NumType),
+    Percentage(NumType),
+    /// An angle, already converted to degrees.
+    Angle(NumType),
+    None,
+}
+
+struct Arguments {
+    channels: Vec<(Component, usize)>,
+    alpha: Option<(Component, usize)>,
+    legacy: bool,
+}
+
+fn error(kind: CssErrorKind, position: usize) -> Exeptions {
+    Exeptions::InvalidCss(CssError {
+        kind,
+        position: position.try_into().unwrap_or(u32::MAX),
+    })
+}
+
+fn is_ident_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_' || byte == b'-'
+}
+
+fn is_ident(byte: u8) -> bool {
+    is_ident_start(byte) || byte.is_ascii_digit()
+}
+
+fn starts_number(bytes: &[u8]) -> bool {
+    let rest = match bytes.first() {
+        Some(b'+' | b'-') => &bytes[1..],
+        _ => bytes,
+    };
+
+    matches!(rest, [b'0'..=b'9', ..] | [b'.', b'0'..=b'9', ..])
+}
+
+fn number_length(bytes: &[u8]) -> usize {
+    let mut i = 0;
+
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        i += 1;
+    }
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i + 1 < bytes.len() && bytes[i] == b'.' && bytes[i + 1].is_ascii_digit() {
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        let mut exponent = i + 1;
+
+        if exponent < bytes.len() && (bytes[exponent] == b'+' || bytes[exponent] == b'-') {
+            exponent += 1;
+        }
+        if exponent < bytes.len() && bytes[exponent].is_ascii_digit() {
+            i = exponent;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+    }
+
+    i
+}
+
+fn tokenize(value: &str) -> Result<Vec<(Token, usize)>, Exeptions> {
+    let bytes = value.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+
+        match bytes[i] {
+            b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => i += 1,
+            b',' => {
+                tokens.push((Token::Comma, start));
+                i += 1;
+            }
+            b'/' => {
+                tokens.push((Token::Slash, start));
+                i += 1;
+            }
+            b')' => {
+                tokens.push((Token::Close, start));
+                i += 1;
+            }
+            _ if starts_number(&bytes[i..]) => {
+                i += number_length(&bytes[i..]);
+                let number = value[start..i]
+                    .parse::<NumType>()
+                    .map_err(|_| error(CssErrorKind::UnexpectedCharacter, start))?;
+                if !number.is_finite() {
+                    return Err(error(CssErrorKind::NumberOutOfRange, start));
+                }
+
+                if i < bytes.len() && bytes[i] == b'%' {
+                    i += 1;
+                    tokens.push((Token::Percentage(number), start));
+                } else if i < bytes.len() && is_ident_start(bytes[i]) {
+                    let unit_start = i;
+                    while i < bytes.len() && is_ident(bytes[i]) {
+                        i += 1;
+                    }
+                    let unit = value[unit_start..i].to_ascii_lowercase();
+                    tokens.push((Token::Dimension(number, unit), start));
+                } else {
+                    tokens.push((Token::Number(number), start));
+                }
+            }
+            byte if is_ident_start(byte) => {
+                while i < bytes.len() && is_ident(bytes[i]) {
+                    i += 1;
+                }
+                let name = value[start..i].to_ascii_lowercase();
+
+                if i < bytes.len() && bytes[i] == b'(' {
+                    i += 1;
+                    tokens.push((Token::Function(name), start));
+                } else {
+                    tokens.push((Token::Ident(name), start));
+                }
+            }
+            _ => return Err(error(CssErrorKind::UnexpectedCharacter, start)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn to_component(token: Token, position: usize) -> Result<Component, Exeptions> {
+    match token {
+        Token::Number(value) => Ok(Component::Number(value)),
+        Token::Percentage(value) => Ok(Component::Percentage(value)),
+        Token::Ident(name) if name == "none" => Ok(Component::None),
+        Token::Dimension(value, unit) => {
+            let degrees = match unit.as_str() {
+                "deg" => value,
+                "rad" => value.to_degrees(),
+                "grad" => value * 0.9,
+                "turn" => value * 360.,
+                _ => return Err(error(CssErrorKind::InvalidUnit, position)),
+            };
+
+            if degrees.is_finite() {
+                Ok(Component::Angle(degrees))
+            } else {
+                Err(error(CssErrorKind::NumberOutOfRange, position))
+            }
+        }
+        _ => Err(error(CssErrorKind::UnexpectedToken, position)),
+    }
+}
+
+/// Reads everything up to the closing parenthesis, working out whether the legacy comma
+/// separated syntax or the modern space separated syntax (with `/ alpha`) was used.
+fn read_arguments<I>(tokens: &mut I, end: usize) -> Result<Arguments, Exeptions>
+where
+    I: Iterator<Item = (Token, usize)>,
+{
+    let mut channels = Vec::new();
+    let mut alpha = None;
+    let mut commas = 0;
+    let mut after_slash = false;
+    let mut expect_component = true;
+
+    loop {
+        let (token, position) = tokens
+            .next()
+            .ok_or_else(|| error(CssErrorKind::UnexpectedEnd, end))?;
+
+        match token {
+            Token::Close => break,
+            Token::Comma => {
+                if expect_component || after_slash || commas != channels.len() - 1 {
+                    return Err(error(CssErrorKind::MixedSeparators, position));
+                }
+                commas += 1;
+                expect_component = true;
+            }
+            Token::Slash => {
+                if expect_component || after_slash || commas > 0 {
+                    return Err(error(CssErrorKind::MixedSeparators, position));
+                }
+                after_slash = true;
+                expect_component = true;
+            }
+            token => {
+                if commas > 0 && !expect_component {
+                    return Err(error(CssErrorKind::MixedSeparators, position));
+                }
+                let component = to_component(token, position)?;
+
+                if after_slash {
+                    if alpha.is_some() {
+                        return Err(error(CssErrorKind::WrongComponentCount, position));
+                    }
+                    alpha = Some((component, position));
+                } else {
+                    channels.push((component, position));
+                }
+                expect_component = false;
+            }
+        }
+    }
+
+    if expect_component && (commas > 0 || after_slash) {
+        return Err(error(CssErrorKind::UnexpectedToken, end));
+    }
+
+    let legacy = commas > 0;
+    if legacy {
+        if let Some(&(_, position)) = channels
+            .iter()
+            .find(|(component, _)| *component == Component::None)
+        {
+            return Err(error(CssErrorKind::UnexpectedToken, position));
+        }
+    }
+
+    Ok(Arguments {
+        channels,
+        alpha,
+        legacy,
+    })
+}
+
+fn number_or_percentage(
+    (component, position): (Component, usize),
+    reference: NumType,
+) -> Result<NumType, Exeptions> {
+    match component {
+        Component::Number(value) => Ok(value),
+        Component::Percentage(value) => Ok(value / 100. * reference),
+        Component::None => Ok(0.),
+        Component::Angle(_) => Err(error(CssErrorKind::InvalidUnit, position)),
+    }
+}
+
+fn hue((component, position): (Component, usize)) -> Result<NumType, Exeptions> {
+    match component {
+        Component::Number(value) | Component::Angle(value) => Ok(value.rem_euclid(360.)),
+        Component::None => Ok(0.),
+        Component::Percentage(_) => Err(error(CssErrorKind::InvalidUnit, position)),
+    }
+}
+
+fn alpha(alpha: Option<(Component, usize)>) -> Result<NumType, Exeptions> {
+    match alpha {
+        Some(alpha) => Ok(number_or_percentage(alpha, 1.)?.clamp(0., 1.)),
+        None => Ok(1.),
+    }
+}
+
+/// Fails when the channels were too large to convert, since clipping would keep them NaN.
+fn finite(color: CssColor, position: usize) -> Result<CssColor, Exeptions> {
+    let (first, second, third) = match color {
+        CssColor::Rgb(rgb) => rgb,
+        CssColor::Hsl(hsl) => hsl,
+    };
+
+    if first.is_finite() && second.is_finite() && third.is_finite() {
+        Ok(color)
+    } else {
+        Err(error(CssErrorKind::NumberOutOfRange, position))
+    }
+}
+
+fn clamp_rgb(rgb: RGB) -> RGB {
+    (
+        rgb.0.clamp(0., 255.),
+        rgb.1.clamp(0., 255.),
+        rgb.2.clamp(0., 255.),
+    )
+}
+
+/// Splits the arguments into three channels and an alpha, allowing a fourth comma
+/// separated alpha only for the functions that had one in CSS Color Level 3.
+fn channels(
+    arguments: Arguments,
+    name_position: usize,
+    legacy_alpha: bool,
+) -> Result<([(Component, usize); 3], NumType), Exeptions> {
+    let Arguments {
+        mut channels,
+        alpha: slash_alpha,
+        legacy,
+    } = arguments;
+
+    if legacy && !legacy_alpha {
+        return Err(error(CssErrorKind::MixedSeparators, name_position));
+    }
+
+    let alpha_value = if legacy && channels.len() == 4 {
+        alpha(channels.pop())?
+    } else {
+        alpha(slash_alpha)?
+    };
+
+    match channels[..] {
+        [first, second, third] => Ok(([first, second, third], alpha_value)),
+        _ => Err(error(CssErrorKind::WrongComponentCount, name_position)),
+    }
+}
+
+fn parse_color_function<I>(
+    tokens: &mut I,
+    name_position: usize,
+    end: usize,
+) -> Result<ParsedCss, Exeptions>
+where
+    I: Iterator<Item = (Token, usize)>,
+{
+    let (space, position) = match tokens.next() {
+        Some((Token::Ident(space), position)) => (space, position),
+        Some((_, position)) => return Err(error(CssErrorKind::UnexpectedToken, position)),
+        None => return Err(error(CssErrorKind::UnexpectedEnd, end)),
+    };
+
+    let ([first, second, third], alpha) =
+        channels(read_arguments(tokens, end)?, name_position, false)?;
+    let first = number_or_percentage(first, 1.)?;
+    let second = number_or_percentage(second, 1.)?;
+    let third = number_or_percentage(third, 1.)?;
+
+    let rgb = match space.as_str() {
+        "srgb" => (first * 255., second * 255., third * 255.),
+        "srgb-linear" => to_rgb::linear_srgb_to_rgb(first, second, third),
+        "display-p3" => to_rgb::display_p3_to_rgb(first, second, third),
+        "xyz" | "xyz-d65" => to_rgb::xyz_to_rgb(first, second, third),
+        "xyz-d50" => to_rgb::xyz_d50_to_rgb(first, second, third),
+        _ => return Err(error(CssErrorKind::UnknownColorSpace, position)),
+    };
+
+    Ok(ParsedCss {
+        color: finite(CssColor::Rgb(clamp_rgb(rgb)), name_position)?,
+        alpha,
+    })
+}
+
+/// A named color keyword such as `rebeccapurple`, or `transparent`.
+fn keyword(name: &str, position: usize) -> Result<ParsedCss, Exeptions> {
+    if name == "transparent" {
+        return Ok(ParsedCss {
+            color: CssColor::Rgb((0., 0., 0.)),
+            alpha: 0.,
+        });
+    }
+
+    names::css_keyword(name)
+        .map(|rgb| ParsedCss {
+            color: CssColor::Rgb(rgb),
+            alpha: 1.,
+        })
+        .ok_or_else(|| error(CssErrorKind::UnknownKeyword, position))
+}
+
+/// Parses a CSS Color Level 4 color function such as `rgb(255 0 0 / 50%)`,
+/// `hsl(120deg, 50%, 50%)` or `color(display-p3 1 0 0)`, or a named color keyword.
+///
+/// Colors that fall outside of the sRGB gamut are clipped.
+pub fn parse(value: &str) -> Result<ParsedCss, Exeptions> {
+    let end = value.len();
+    let mut tokens = tokenize(value)?.into_iter();
+
+    let (name, name_position) = match tokens.next() {
+        Some((Token::Function(name), position)) => (name, position),
+        Some((Token::Ident(name), position)) => {
+            let parsed = keyword(&name, position)?;
+
+            return match tokens.next() {
+                Some((_, position)) => Err(error(CssErrorKind::UnexpectedToken, position)),
+                None => Ok(parsed),
+            };
+        }
+        Some((_, position)) => return Err(error(CssErrorKind::UnexpectedToken, position)),
+        None => return Err(error(CssErrorKind::UnexpectedEnd, end)),
+    };
+
+    let parsed = if name == "color" {
+        parse_color_function(&mut tokens, name_position, end)?
+    } else {
+        let legacy_alpha = matches!(name.as_str(), "rgb" | "rgba" | "hsl" | "hsla");
+        let ([first, second, third], alpha) = channels(
+            read_arguments(&mut tokens, end)?,
+            name_position,
+            legacy_alpha,
+        )?;
+
+        let color = match name.as_str() {
+            "rgb" | "rgba" => CssColor::Rgb(clamp_rgb((
+                number_or_percentage(first, 255.)?,
+                number_or_percentage(second, 255.)?,
+                number_or_percentage(third, 255.)?,
+            ))),
+            "hsl" | "hsla" => CssColor::Hsl((
+                hue(first)?,
+                (number_or_percentage(second, 100.)? / 100.).clamp(0., 1.),
+                (number_or_percentage(third, 100.)? / 100.).clamp(0., 1.),
+            )),
+            "hwb" => CssColor::Rgb(clamp_rgb(to_rgb::hwb_to_rgb(
+                hue(first)?,
+                (number_or_percentage(second, 100.)? / 100.).clamp(0., 1.),
+                (number_or_percentage(third, 100.)? / 100.).clamp(0., 1.),
+            ))),
+            "lab" => CssColor::Rgb(clamp_rgb(to_rgb::lab_to_rgb(
+                number_or_percentage(first, 100.)?.clamp(0., 100.),
+                number_or_percentage(second, 125.)?,
+                number_or_percentage(third, 125.)?,
+            ))),
+            "lch" => CssColor::Rgb(clamp_rgb(to_rgb::lch_to_rgb(
+                number_or_percentage(first, 100.)?.clamp(0., 100.),
+                number_or_percentage(second, 150.)?.max(0.),
+                hue(third)?,
+            ))),
+            "oklab" => CssColor::Rgb(clamp_rgb(to_rgb::oklab_to_rgb(
+                number_or_percentage(first, 1.)?.clamp(0., 1.),
+                number_or_percentage(second, 0.4)?,
+                number_or_percentage(third, 0.4)?,
+            ))),
+            "oklch" => CssColor::Rgb(clamp_rgb(to_rgb::oklch_to_rgb(
+                number_or_percentage(first, 1.)?.clamp(0., 1.),
+                number_or_percentage(second, 0.4)?.max(0.),
+                hue(third)?,
+            ))),
+            _ => return Err(error(CssErrorKind::UnknownFunction, name_position)),
+        };
+
+        ParsedCss {
+            color: finite(color, name_position)?,
+            alpha,
+        }
+    };
+
+    if let Some((_, position)) = tokens.next() {
+        return Err(error(CssErrorKind::UnexpectedToken, position));
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(value: &str) -> (RGB, NumType) {
+        match parse(value) {
+            Ok(ParsedCss {
+                color: CssColor::Rgb(rgb),
+                alpha,
+            }) => (rgb, alpha),
+            _ => panic!("{} did not parse as rgb", value),
+        }
+    }
+
+    fn kind(value: &str) -> (CssErrorKind, u32) {
+        match parse(value) {
+            Err(Exeptions::InvalidCss(error)) => (error.kind, error.position),
+            _ => panic!("{} did not fail to parse", value),
+        }
+    }
+
+    fn assert_close(left: RGB, right: RGB) {
+        assert!(
+            (left.0 - right.0).abs() <= 0.5
+                && (left.1 - right.1).abs() <= 0.5
+                && (left.2 - right.2).abs() <= 0.5,
+            "{:?} != {:?}",
+            left,
+            right
+        );
+    }
+
+    #[test]
+    fn rgb_syntaxes() {
+        assert_eq!(rgb("rgb(255, 128, 0)"), ((255., 128., 0.), 1.));
+        assert_eq!(rgb("RGBA(255, 128, 0, 0.5)"), ((255., 128., 0.), 0.5));
+        assert_eq!(rgb("rgb(100% 50% none / 25%)"), ((255., 127.5, 0.), 0.25));
+        assert_eq!(rgb("rgb(300 -5 1e2)"), ((255., 0., 100.), 1.));
+    }
+
+    #[test]
+    fn hsl_syntaxes() {
+        for value in [
+            "hsl(120, 50%, 25%)",
+            "hsl(120deg 50% 25%)",
+            "hsl(0.3333333333333333turn 50 25)",
+            "hsla(133.33333333333334grad, 50%, 25%, 1)",
+            "hsl(480 50% 25%)",
+        ] {
+            match parse(value) {
+                Ok(ParsedCss {
+                    color: CssColor::Hsl(hsl),
+                    alpha,
+                }) => {
+                    assert!((hsl.0 - 120.).abs() < 1e-9, "{}", value);
+                    assert_eq!((hsl.1, hsl.2, alpha), (0.5, 0.25, 1.), "{}", value);
+                }
+                _ => panic!("{} did not parse as hsl", value),
+            }
+        }
+    }
+
+    #[test]
+    fn device_independent_spaces() {
+        assert_close(rgb("hwb(0 0% 0%)").0, (255., 0., 0.));
+        assert_close(rgb("hwb(90 60% 60%)").0, (127.5, 127.5, 127.5));
+        assert_close(rgb("lab(50 0 0)").0, (119., 119., 119.));
+        assert_close(rgb("lab(54.29% 80.8 69.89)").0, (255., 0., 0.));
+        assert_close(rgb("lch(54.29 106.84 40.85deg)").0, (255., 0., 0.));
+        assert_close(rgb("oklab(0.62796 0.22486 0.12585)").0, (255., 0., 0.));
+        assert_close(rgb("oklch(62.796% 0.25768 29.2339)").0, (255., 0., 0.));
+        assert_close(rgb("color(srgb 1 0.5 0)").0, (255., 127.5, 0.));
+        assert_close(rgb("color(display-p3 1 0 0)").0, (255., 0., 0.));
+        assert_close(rgb("color(xyz-d65 0.9505 1 1.089)").0, (255., 255., 255.));
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(rgb("RebeccaPurple"), ((102., 51., 153.), 1.));
+        assert_eq!(rgb(" transparent "), ((0., 0., 0.), 0.));
+        assert_eq!(kind("navyblue"), (CssErrorKind::UnknownKeyword, 0));
+        assert_eq!(kind("light-blue"), (CssErrorKind::UnknownKeyword, 0));
+        assert_eq!(rgb("LightBlue"), ((173., 216., 230.), 1.));
+        assert_eq!(kind("red blue"), (CssErrorKind::UnexpectedToken, 4));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(kind("rgb(1 2)"), (CssErrorKind::WrongComponentCount, 0));
+        assert_eq!(kind("rgb(1, 2 3)"), (CssErrorKind::MixedSeparators, 9));
+        assert_eq!(kind("rgb(1 2 3"), (CssErrorKind::UnexpectedEnd, 9));
+        assert_eq!(kind("rgb(1 2 3) x"), (CssErrorKind::UnexpectedToken, 11));
+        assert_eq!(
+            kind("rgb(1 2 3 / 1 2)"),
+            (CssErrorKind::WrongComponentCount, 14)
+        );
+        assert_eq!(kind("hsl(10px 1 1)"), (CssErrorKind::InvalidUnit, 4));
+        assert_eq!(kind("hsl(10% 1 1)"), (CssErrorKind::InvalidUnit, 4));
+        assert_eq!(kind("lab(1, 2, 3)"), (CssErrorKind::MixedSeparators, 0));
+        assert_eq!(kind("rgb(none, 2, 3)"), (CssErrorKind::UnexpectedToken, 4));
+        assert_eq!(kind("foo(1 2 3)"), (CssErrorKind::UnknownFunction, 0));
+        assert_eq!(
+            kind("color(rec2020 1 0 0)"),
+            (CssErrorKind::UnknownColorSpace, 6)
+        );
+        assert_eq!(kind("rgb(1 2 $)"), (CssErrorKind::UnexpectedCharacter, 8));
+        assert_eq!(kind(""), (CssErrorKind::UnexpectedEnd, 0));
+    }
+
+    #[test]
+    fn non_finite_numbers() {
+        assert_eq!(
+            kind("hsl(1e999 50% 50%)"),
+            (CssErrorKind::NumberOutOfRange, 4)
+        );
+        assert_eq!(
+            kind("hwb(1e999 10% 10%)"),
+            (CssErrorKind::NumberOutOfRange, 4)
+        );
+        assert_eq!(kind("lab(50 1e999 0)"), (CssErrorKind::NumberOutOfRange, 7));
+        assert_eq!(
+            kind("oklch(0.5 0.1 1e999)"),
+            (CssErrorKind::NumberOutOfRange, 14)
+        );
+        assert_eq!(
+            kind("rgb(-1e999% 0 0)"),
+            (CssErrorKind::NumberOutOfRange, 4)
+        );
+        assert_eq!(
+            kind("hsl(1e308turn 50% 50%)"),
+            (CssErrorKind::NumberOutOfRange, 4)
+        );
+        assert_eq!(kind("lab(50 1e300 0)"), (CssErrorKind::NumberOutOfRange, 0));
+        assert_eq!(
+            kind("oklch(0.5 1e200 0)"),
+            (CssErrorKind::NumberOutOfRange, 0)
+        );
+    }
+}
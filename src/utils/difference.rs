@@ -0,0 +1,294 @@
+use crate::color_print::NumType;
+use crate::utils::helper::*;
+use crate::utils::types::*;
+
+pub fn delta_e_76(lab1: LAB, lab2: LAB) -> NumType {
+    ((lab1.0 - lab2.0).powi(2) + (lab1.1 - lab2.1).powi(2) + (lab1.2 - lab2.2).powi(2)).sqrt()
+}
+
+/// CIE94, with the graphic arts weights or, when `textiles` is set, the textile weights.
+pub fn delta_e_94(lab1: LAB, lab2: LAB, textiles: bool) -> NumType {
+    let (kl, k1, k2) = if textiles {
+        (2., 0.048, 0.014)
+    } else {
+        (1., 0.045, 0.015)
+    };
+
+    let c1 = (lab1.1 * lab1.1 + lab1.2 * lab1.2).sqrt();
+    let c2 = (lab2.1 * lab2.1 + lab2.2 * lab2.2).sqrt();
+    let delta_l = lab1.0 - lab2.0;
+    let delta_c = c1 - c2;
+    let delta_h_squared =
+        ((lab1.1 - lab2.1).powi(2) + (lab1.2 - lab2.2).powi(2) - delta_c.powi(2)).max(0.);
+
+    let sc = 1. + k1 * c1;
+    let sh = 1. + k2 * c1;
+
+    ((delta_l / kl).powi(2) + (delta_c / sc).powi(2) + delta_h_squared / sh.powi(2)).sqrt()
+}
+
+/// CIEDE2000, following Sharma, Wu and Dalal's implementation notes.
+pub fn delta_e_2000(lab1: LAB, lab2: LAB) -> NumType {
+    let (l1, a1, b1) = lab1;
+    let (l2, a2, b2) = lab2;
+
+    let c_mean = ((a1 * a1 + b1 * b1).sqrt() + (a2 * a2 + b2 * b2).sqrt()) / 2.;
+    let g = 0.5 * (1. - (c_mean.powi(7) / (c_mean.powi(7) + 25_f64.powi(7))).sqrt());
+
+    let a1 = a1 * (1. + g);
+    let a2 = a2 * (1. + g);
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+    let c2 = (a2 * a2 + b2 * b2).sqrt();
+    let hue = |a: NumType, b: NumType| {
+        if a == 0. && b == 0. {
+            0.
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.)
+        }
+    };
+    let h1 = hue(a1, b1);
+    let h2 = hue(a2, b2);
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = if c1 * c2 == 0. {
+        0.
+    } else if (h2 - h1).abs() <= 180. {
+        h2 - h1
+    } else if h2 - h1 > 180. {
+        h2 - h1 - 360.
+    } else {
+        h2 - h1 + 360.
+    };
+    let delta_h = 2. * (c1 * c2).sqrt() * (delta_h / 2.).to_radians().sin();
+
+    let l_mean = (l1 + l2) / 2.;
+    let c_mean = (c1 + c2) / 2.;
+    let h_mean = if c1 * c2 == 0. {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180. {
+        (h1 + h2) / 2.
+    } else if h1 + h2 < 360. {
+        (h1 + h2 + 360.) / 2.
+    } else {
+        (h1 + h2 - 360.) / 2.
+    };
+
+    let t = 1. - 0.17 * (h_mean - 30.).to_radians().cos()
+        + 0.24 * (2. * h_mean).to_radians().cos()
+        + 0.32 * (3. * h_mean + 6.).to_radians().cos()
+        - 0.20 * (4. * h_mean - 63.).to_radians().cos();
+    let delta_theta = 30. * (-((h_mean - 275.) / 25.).powi(2)).exp();
+    let rc = 2. * (c_mean.powi(7) / (c_mean.powi(7) + 25_f64.powi(7))).sqrt();
+    let sl = 1. + 0.015 * (l_mean - 50.).powi(2) / (20. + (l_mean - 50.).powi(2)).sqrt();
+    let sc = 1. + 0.045 * c_mean;
+    let sh = 1. + 0.015 * c_mean * t;
+    let rt = -(2. * delta_theta).to_radians().sin() * rc;
+
+    ((delta_l / sl).powi(2)
+        + (delta_c / sc).powi(2)
+        + (delta_h / sh).powi(2)
+        + rt * (delta_c / sc) * (delta_h / sh))
+        .sqrt()
+}
+
+/// CMC l:c, measuring how far `lab2` is from the reference color `lab1`.
+///
+/// `lightness` and `chroma` must be positive, usually 2:1 for acceptability and 1:1 for
+/// perceptibility; zero or negative weights give infinity or NaN.
+pub fn delta_e_cmc(lab1: LAB, lab2: LAB, lightness: NumType, chroma: NumType) -> NumType {
+    let c1 = (lab1.1 * lab1.1 + lab1.2 * lab1.2).sqrt();
+    let c2 = (lab2.1 * lab2.1 + lab2.2 * lab2.2).sqrt();
+    let delta_l = lab1.0 - lab2.0;
+    let delta_c = c1 - c2;
+    let delta_h_squared =
+        ((lab1.1 - lab2.1).powi(2) + (lab1.2 - lab2.2).powi(2) - delta_c.powi(2)).max(0.);
+
+    let h1 = lab1.2.atan2(lab1.1).to_degrees().rem_euclid(360.);
+    let t = if (164. ..=345.).contains(&h1) {
+        0.56 + 0.2 * (h1 + 168.).to_radians().cos().abs()
+    } else {
+        0.36 + 0.4 * (h1 + 35.).to_radians().cos().abs()
+    };
+    let f = (c1.powi(4) / (c1.powi(4) + 1900.)).sqrt();
+
+    let sl = if lab1.0 < 16. {
+        0.511
+    } else {
+        0.040975 * lab1.0 / (1. + 0.01765 * lab1.0)
+    };
+    let sc = 0.0638 * c1 / (1. + 0.0131 * c1) + 0.638;
+    let sh = sc * (f * t + 1. - f);
+
+    ((delta_l / (lightness * sl)).powi(2)
+        + (delta_c / (chroma * sc)).powi(2)
+        + delta_h_squared / sh.powi(2))
+    .sqrt()
+}
+
+const XYZ_TO_ICTCP_LMS: [[f64; 3]; 3] = [
+    [0.35928325901212155, 0.69760511477795, -0.035891593232028875],
+    [-0.1920808463704994, 1.100476797037432, 0.07537486585191189],
+    [
+        0.007079784460747815,
+        0.07483966621863648,
+        0.8433265453898765,
+    ],
+];
+
+const LMS_TO_ICTCP: [[f64; 3]; 3] = [
+    [2048. / 4096., 2048. / 4096., 0.],
+    [6610. / 4096., -13613. / 4096., 7003. / 4096.],
+    [17933. / 4096., -17390. / 4096., -543. / 4096.],
+];
+
+/// The SMPTE ST 2084 (PQ) transfer function, taking luminance in cd/m².
+fn perceptual_quantizer(luminance: NumType) -> NumType {
+    let m1 = 2610. / 16384.;
+    let m2 = 2523. / 32.;
+    let c1 = 3424. / 4096.;
+    let c2 = 2413. / 128.;
+    let c3 = 2392. / 128.;
+
+    let y = (luminance.max(0.) / 10000.).powf(m1);
+
+    ((c1 + c2 * y) / (1. + c3 * y)).powf(m2)
+}
+
+/// Converts CIE XYZ relative to D65 into ICtCp, treating `y` of 1 as SDR reference white (203 cd/m²).
+pub fn xyz_to_ictcp(xyz: XYZ) -> (NumType, NumType, NumType) {
+    let absolute = (xyz.0 * 203., xyz.1 * 203., xyz.2 * 203.);
+    let lms = multiply_matrix(&XYZ_TO_ICTCP_LMS, absolute);
+    let lms = (
+        perceptual_quantizer(lms.0),
+        perceptual_quantizer(lms.1),
+        perceptual_quantizer(lms.2),
+    );
+
+    multiply_matrix(&LMS_TO_ICTCP, lms)
+}
+
+/// Delta E ITP (ITU-R BT.2124) between two colors given as CIE XYZ relative to D65.
+pub fn delta_e_itp(xyz1: XYZ, xyz2: XYZ) -> NumType {
+    let ictcp1 = xyz_to_ictcp(xyz1);
+    let ictcp2 = xyz_to_ictcp(xyz2);
+
+    720. * ((ictcp1.0 - ictcp2.0).powi(2)
+        + (0.5 * (ictcp1.1 - ictcp2.1)).powi(2)
+        + (ictcp1.2 - ictcp2.2).powi(2))
+    .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula: Implementation Notes,
+    // Supplementary Test Data, and Mathematical Observations" (2005), table 1.
+    const SHARMA: [(LAB, LAB, NumType); 34] = [
+        ((50., 2.6772, -79.7751), (50., 0., -82.7485), 2.0425),
+        ((50., 3.1571, -77.2803), (50., 0., -82.7485), 2.8615),
+        ((50., 2.8361, -74.02), (50., 0., -82.7485), 3.4412),
+        ((50., -1.3802, -84.2814), (50., 0., -82.7485), 1.),
+        ((50., -1.1848, -84.8006), (50., 0., -82.7485), 1.),
+        ((50., -0.9009, -85.5211), (50., 0., -82.7485), 1.),
+        ((50., 0., 0.), (50., -1., 2.), 2.3669),
+        ((50., -1., 2.), (50., 0., 0.), 2.3669),
+        ((50., 2.49, -0.001), (50., -2.49, 0.0009), 7.1792),
+        ((50., 2.49, -0.001), (50., -2.49, 0.001), 7.1792),
+        ((50., 2.49, -0.001), (50., -2.49, 0.0011), 7.2195),
+        ((50., 2.49, -0.001), (50., -2.49, 0.0012), 7.2195),
+        ((50., -0.001, 2.49), (50., 0.0009, -2.49), 4.8045),
+        ((50., -0.001, 2.49), (50., 0.001, -2.49), 4.8045),
+        ((50., -0.001, 2.49), (50., 0.0011, -2.49), 4.7461),
+        ((50., 2.5, 0.), (50., 0., -2.5), 4.3065),
+        ((50., 2.5, 0.), (73., 25., -18.), 27.1492),
+        ((50., 2.5, 0.), (61., -5., 29.), 22.8977),
+        ((50., 2.5, 0.), (56., -27., -3.), 31.903),
+        ((50., 2.5, 0.), (58., 24., 15.), 19.4535),
+        ((50., 2.5, 0.), (50., 3.1736, 0.5854), 1.),
+        ((50., 2.5, 0.), (50., 3.2972, 0.), 1.),
+        ((50., 2.5, 0.), (50., 1.8634, 0.5757), 1.),
+        ((50., 2.5, 0.), (50., 3.2592, 0.335), 1.),
+        (
+            (60.2574, -34.0099, 36.2677),
+            (60.4626, -34.1751, 39.4387),
+            1.2644,
+        ),
+        (
+            (63.0109, -31.0961, -5.8663),
+            (62.8187, -29.7946, -4.0864),
+            1.263,
+        ),
+        ((61.2901, 3.7196, -5.3901), (61.4292, 2.248, -4.962), 1.8731),
+        (
+            (35.0831, -44.1164, 3.7933),
+            (35.0232, -40.0716, 1.5901),
+            1.8645,
+        ),
+        (
+            (22.7233, 20.0904, -46.694),
+            (23.0331, 14.973, -42.5619),
+            2.0373,
+        ),
+        (
+            (36.4612, 47.858, 18.3852),
+            (36.2715, 50.5065, 21.2231),
+            1.4146,
+        ),
+        (
+            (90.8027, -2.0831, 1.441),
+            (91.1528, -1.6435, 0.0447),
+            1.4441,
+        ),
+        (
+            (90.9257, -0.5406, -0.9208),
+            (88.6381, -0.8985, -0.7239),
+            1.5381,
+        ),
+        (
+            (6.7747, -0.2908, -2.4247),
+            (5.8714, -0.0985, -2.2286),
+            0.6377,
+        ),
+        ((2.0776, 0.0795, -1.135), (0.9033, -0.0636, -0.5514), 0.9082),
+    ];
+
+    #[test]
+    fn ciede2000_sharma() {
+        for (i, (lab1, lab2, expected)) in SHARMA.into_iter().enumerate() {
+            let forward = delta_e_2000(lab1, lab2);
+            let backward = delta_e_2000(lab2, lab1);
+
+            assert!(
+                (forward - expected).abs() < 0.0001 && (backward - expected).abs() < 0.0001,
+                "pair {} gave {} and {} instead of {}",
+                i + 1,
+                forward,
+                backward,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn other_metrics() {
+        let lab1 = (50., 2.5, 0.);
+        let lab2 = (73., 25., -18.);
+
+        assert!((delta_e_76(lab1, lab2) - 36.8680).abs() < 0.0001);
+        assert!((delta_e_94(lab1, lab2, false) - 34.6892).abs() < 0.0001);
+        assert!((delta_e_94(lab1, lab2, true) - 28.2503).abs() < 0.0001);
+        assert!((delta_e_cmc(lab1, lab2, 2., 1.) - 37.9233).abs() < 0.0001);
+        assert_eq!(delta_e_itp((0.5, 0.5, 0.5), (0.5, 0.5, 0.5)), 0.);
+    }
+
+    #[test]
+    fn ictcp_white() {
+        let ictcp = xyz_to_ictcp(D65_WHITE);
+
+        assert!((ictcp.0 - 0.5806).abs() < 0.001);
+        assert!(ictcp.1.abs() < 0.001);
+        assert!(ictcp.2.abs() < 0.001);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::utils::helper::{max, min};
+use crate::color_print::WhitePoint;
+use crate::utils::helper::*;
 use crate::utils::types::*;
 
 pub fn rgb_to_hsl(r: f64, g: f64, b: f64) -> HSL {
@@ -31,7 +32,7 @@ pub fn rgb_to_hsl(r: f64, g: f64, b: f64) -> HSL {
     if delta == 0. {
         s = 0.
     } else {
-        s = delta / (1. - 2. * l - 1.).abs()
+        s = delta / (1. - (2. * l - 1.).abs())
     }
 
     (h, s, l)
@@ -79,9 +80,51 @@ pub fn rgb_to_cmyk(r: f64, g: f64, b: f64) -> CMYK {
     let b = b / 255.;
 
     k = 1. - max(r, g, b);
+    if k == 1. {
+        return (0., 0., 0., 1.);
+    }
     c = (1. - r - k) / (1. - k);
     m = (1. - g - k) / (1. - k);
     y = (1. - b - k) / (1. - k);
 
     (c, m, y, k)
 }
+
+/// Converts sRGB into CIE XYZ relative to the D65 white point, with `y` of white being 1.
+pub fn rgb_to_xyz(r: f64, g: f64, b: f64) -> XYZ {
+    let linear = (
+        srgb_to_linear(r / 255.),
+        srgb_to_linear(g / 255.),
+        srgb_to_linear(b / 255.),
+    );
+
+    multiply_matrix(&LINEAR_SRGB_TO_XYZ, linear)
+}
+
+/// Converts sRGB into CIE L*a*b* relative to the D50 white point, as used by CSS.
+pub fn rgb_to_lab(r: f64, g: f64, b: f64) -> LAB {
+    rgb_to_lab_with_white_point(r, g, b, WhitePoint::D50)
+}
+
+pub fn rgb_to_lab_with_white_point(r: f64, g: f64, b: f64, white_point: WhitePoint) -> LAB {
+    xyz_d65_to_lab(rgb_to_xyz(r, g, b), white_point)
+}
+
+pub fn rgb_to_lch(r: f64, g: f64, b: f64) -> LCH {
+    to_polar(rgb_to_lab(r, g, b))
+}
+
+pub fn rgb_to_oklab(r: f64, g: f64, b: f64) -> OKLAB {
+    let linear = (
+        srgb_to_linear(r / 255.),
+        srgb_to_linear(g / 255.),
+        srgb_to_linear(b / 255.),
+    );
+    let lms = multiply_matrix(&LINEAR_SRGB_TO_LMS, linear);
+
+    multiply_matrix(&LMS_TO_OKLAB, (lms.0.cbrt(), lms.1.cbrt(), lms.2.cbrt()))
+}
+
+pub fn rgb_to_oklch(r: f64, g: f64, b: f64) -> OKLCH {
+    to_polar(rgb_to_oklab(r, g, b))
+}
@@ -0,0 +1,108 @@
+use crate::color_print::NumType;
+use crate::utils::types::*;
+use crate::utils::{from_rgb, helper, to_rgb};
+
+/// The OKLab distance below which clipping is considered unnoticeable.
+const JND: NumType = 0.02;
+const EPSILON: NumType = 0.0001;
+
+/// Whether `rgb` fits in sRGB, allowing for rounding in the conversions.
+pub fn in_gamut(rgb: RGB) -> bool {
+    [rgb.0, rgb.1, rgb.2]
+        .iter()
+        .all(|channel| (-1e-9..=255. + 1e-9).contains(channel))
+}
+
+fn clip(rgb: RGB) -> RGB {
+    (
+        rgb.0.clamp(0., 255.),
+        rgb.1.clamp(0., 255.),
+        rgb.2.clamp(0., 255.),
+    )
+}
+
+fn delta_e_ok(rgb: RGB, oklab: OKLAB) -> NumType {
+    let clipped = from_rgb::rgb_to_oklab(rgb.0, rgb.1, rgb.2);
+
+    ((clipped.0 - oklab.0).powi(2) + (clipped.1 - oklab.1).powi(2) + (clipped.2 - oklab.2).powi(2))
+        .sqrt()
+}
+
+/// Maps an OKLCH color into sRGB with the CSS Color Level 4 algorithm: the chroma is reduced
+/// until clipping what is left no longer makes a noticeable difference.
+pub fn map_oklch((lightness, chroma, hue): OKLCH) -> RGB {
+    if lightness >= 1. {
+        return (255., 255., 255.);
+    } else if lightness <= 0. {
+        return (0., 0., 0.);
+    }
+
+    let at = |chroma: NumType| {
+        let oklab = helper::from_polar((lightness, chroma, hue));
+        (to_rgb::oklab_to_rgb(oklab.0, oklab.1, oklab.2), oklab)
+    };
+
+    let (origin, oklab) = at(chroma);
+    if in_gamut(origin) {
+        return origin;
+    }
+
+    let mut clipped = clip(origin);
+    if delta_e_ok(clipped, oklab) < JND {
+        return clipped;
+    }
+
+    let (mut min, mut max, mut min_in_gamut) = (0., chroma, true);
+    while max - min > EPSILON {
+        let chroma = (min + max) / 2.;
+        let (current, oklab) = at(chroma);
+
+        if min_in_gamut && in_gamut(current) {
+            min = chroma;
+            continue;
+        }
+
+        clipped = clip(current);
+        let delta = delta_e_ok(clipped, oklab);
+        if delta < JND {
+            if JND - delta < EPSILON {
+                return clipped;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping() {
+        let red = from_rgb::rgb_to_oklch(255., 0., 0.);
+        let mapped = map_oklch(red);
+        assert!((mapped.0 - 255.).abs() < 1e-6 && mapped.1.abs() < 1e-6);
+
+        // Lighter red at the same chroma is out of gamut. Mapping lowers the chroma and keeps the
+        // lightness and hue to within about a just noticeable difference.
+        let lighter = (red.0 + 0.1, red.1, red.2);
+        assert!(!in_gamut(to_rgb::oklch_to_rgb(
+            lighter.0, lighter.1, lighter.2
+        )));
+
+        let mapped = map_oklch(lighter);
+        let oklch = from_rgb::rgb_to_oklch(mapped.0, mapped.1, mapped.2);
+        assert!(in_gamut(mapped));
+        assert!((oklch.0 - lighter.0).abs() < JND);
+        assert!((oklch.2 - lighter.2).abs() < 2.);
+        assert!(oklch.1 < lighter.1);
+
+        assert_eq!(map_oklch((1.2, 0.3, 40.)), (255., 255., 255.));
+        assert_eq!(map_oklch((0., 0.3, 40.)), (0., 0., 0.));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::color_print::{Color as _, ColorDepth, ColorStandered, Easing, HueMethod, NumType};
+use crate::utils::interpolate;
+use crate::utils::terminal;
+use crate::utils::value::ColorValue;
+use crate::Color as ColorStruct;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Reshapes the fraction of the way between two stops.
+pub fn ease(t: NumType, easing: Easing) -> NumType {
+    match easing {
+        Easing::Linear => t,
+        Easing::EaseIn => t * t,
+        Easing::EaseOut => t * (2. - t),
+        Easing::EaseInOut => t * t * (3. - 2. * t),
+    }
+}
+
+/// `ColorStandered::None` interpolates in RGB.
+fn interpolation_space(space: ColorStandered) -> ColorStandered {
+    match space {
+        ColorStandered::None => ColorStandered::Rgb,
+        space => space,
+    }
+}
+
+fn between(
+    from: &ColorStruct,
+    to: &ColorStruct,
+    t: NumType,
+    space: ColorStandered,
+    method: HueMethod,
+) -> ColorStruct {
+    let from = from.value().into_standered(space);
+    let to = to.value().into_standered(space);
+
+    let (components, alpha) = interpolate::interpolate_premultiplied(
+        (from.components(), from.alpha()),
+        (to.components(), to.alpha()),
+        t,
+        space,
+        method,
+    );
+
+    ColorValue::new_unchecked(space, components, alpha).into()
+}
+
+/// The color `t` of the way along evenly spaced `stops`, interpolated in `space`.
+///
+/// `ColorStandered::None` interpolates in RGB, and a NaN `t` gives the first stop. Returns `None`
+/// when there are no stops.
+pub fn sample(
+    stops: &[ColorStruct],
+    t: NumType,
+    space: ColorStandered,
+    method: HueMethod,
+) -> Option<ColorStruct> {
+    let space = interpolation_space(space);
+    let last = stops.len().checked_sub(1)?;
+
+    let t = if t.is_nan() { 0. } else { t.clamp(0., 1.) };
+    let position = t * last as NumType;
+    let index = (position.floor() as usize).min(last.saturating_sub(1));
+    let to = (index + 1).min(last);
+
+    Some(between(
+        &stops[index],
+        &stops[to],
+        position - index as NumType,
+        space,
+        method,
+    ))
+}
+
+/// The color at `t` along `stops`, given as colors with ascending positions, with `easing`
+/// applied between each pair of stops.
+///
+/// Before the first stop and after the last one their colors are held. Where two stops share a
+/// position the later one wins, giving a hard edge. Returns `None` when there are no stops.
+pub fn sample_positioned(
+    stops: &[(ColorStruct, NumType)],
+    t: NumType,
+    space: ColorStandered,
+    method: HueMethod,
+    easing: Easing,
+) -> Option<ColorStruct> {
+    let space = interpolation_space(space);
+    let (first, last) = (stops.first()?, stops.last()?);
+
+    let Some(to) = stops.iter().position(|(_, position)| *position > t) else {
+        return Some(between(&last.0, &last.0, 0., space, method));
+    };
+    if to == 0 {
+        return Some(between(&first.0, &first.0, 0., space, method));
+    }
+
+    let (from, to) = (&stops[to - 1], &stops[to]);
+    let t = (t - from.1) / (to.1 - from.1);
+
+    Some(between(&from.0, &to.0, ease(t, easing), space, method))
+}
+
+/// Colors each grapheme of `text` along `stops`, ending with a foreground reset.
+///
+/// Without a depth the one detected for the terminal is used.
+pub fn paint_text(
+    text: &str,
+    stops: &[ColorStruct],
+    space: ColorStandered,
+    method: HueMethod,
+    depth: Option<ColorDepth>,
+) -> String {
+    let depth = depth.unwrap_or_else(terminal::detect_depth);
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+
+    if stops.is_empty() || graphemes.is_empty() || depth == ColorDepth::None {
+        return text.to_string();
+    }
+
+    let steps = graphemes.len().saturating_sub(1).max(1) as NumType;
+    let mut painted = String::new();
+
+    for (index, grapheme) in graphemes.iter().enumerate() {
+        if let Some(color) = sample(stops, index as NumType / steps, space, method) {
+            painted.push_str(&color.to_string_no_handle(None, None, Some(depth)));
+        }
+        painted.push_str(grapheme);
+    }
+    painted.push_str("\x1b[39m");
+
+    painted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampling() {
+        let stops = [
+            ColorStruct::new_rgb(255., 0., 0.).unwrap(),
+            ColorStruct::new_rgb(0., 0., 255.).unwrap(),
+            ColorStruct::new_rgb(0., 255., 0.).unwrap(),
+        ];
+        let rgb = |t, space, method| {
+            let rgb = sample(&stops, t, space, method).unwrap().rgb_value();
+            (rgb.0.round(), rgb.1.round(), rgb.2.round())
+        };
+
+        assert_eq!(
+            rgb(0., ColorStandered::Rgb, HueMethod::Shorter),
+            (255., 0., 0.)
+        );
+        assert_eq!(
+            rgb(0.25, ColorStandered::Rgb, HueMethod::Shorter),
+            (128., 0., 128.)
+        );
+        assert_eq!(
+            rgb(1., ColorStandered::Rgb, HueMethod::Shorter),
+            (0., 255., 0.)
+        );
+        // Halfway from red to blue is magenta the short way round and green the long way.
+        assert_eq!(
+            rgb(0.25, ColorStandered::Hsl, HueMethod::Shorter),
+            (255., 0., 255.)
+        );
+        assert_eq!(
+            rgb(0.25, ColorStandered::Hsl, HueMethod::Longer),
+            (0., 255., 0.)
+        );
+        assert!(sample(&[], 0.5, ColorStandered::Rgb, HueMethod::Shorter).is_none());
+    }
+
+    #[test]
+    fn text() {
+        let stops = [
+            ColorStruct::new_rgb(255., 0., 0.).unwrap(),
+            ColorStruct::new_rgb(0., 0., 255.).unwrap(),
+        ];
+
+        assert_eq!(
+            paint_text(
+                "ae\u{301}o",
+                &stops,
+                ColorStandered::Rgb,
+                HueMethod::Shorter,
+                Some(ColorDepth::Truecolor)
+            ),
+            "\x1b[38;2;255;0;0ma\x1b[38;2;127;0;127me\u{301}\x1b[38;2;0;0;255mo\x1b[39m"
+        );
+        assert_eq!(
+            paint_text(
+                "abc",
+                &stops,
+                ColorStandered::Rgb,
+                HueMethod::Shorter,
+                Some(ColorDepth::None)
+            ),
+            "abc"
+        );
+    }
+
+    #[test]
+    fn positioned() {
+        let stops = [
+            (ColorStruct::new_rgb(255., 0., 0.).unwrap(), 0.2),
+            (ColorStruct::new_rgb(0., 0., 255.).unwrap(), 0.6),
+            (ColorStruct::new_rgb(0., 255., 0.).unwrap(), 0.6),
+            (ColorStruct::new_rgb(0., 0., 0.).unwrap(), 1.),
+        ];
+        let rgb = |t, easing| {
+            let color =
+                sample_positioned(&stops, t, ColorStandered::Rgb, HueMethod::Shorter, easing);
+            let rgb = color.unwrap().rgb_value();
+            (rgb.0.round(), rgb.1.round(), rgb.2.round())
+        };
+
+        assert_eq!(rgb(0., Easing::Linear), (255., 0., 0.));
+        assert_eq!(rgb(0.3, Easing::Linear), (191., 0., 64.));
+        assert_eq!(rgb(0.3, Easing::EaseIn), (239., 0., 16.));
+        assert_eq!(rgb(0.3, Easing::EaseOut), (143., 0., 112.));
+        assert_eq!(rgb(0.3, Easing::EaseInOut), (215., 0., 40.));
+        assert_eq!(rgb(0.5999, Easing::Linear), (0., 0., 255.));
+        assert_eq!(rgb(0.6, Easing::Linear), (0., 255., 0.));
+        assert_eq!(rgb(0.9, Easing::Linear), (0., 64., 0.));
+        assert_eq!(rgb(2., Easing::Linear), (0., 0., 0.));
+        assert!(sample_positioned(
+            &[],
+            0.5,
+            ColorStandered::Rgb,
+            HueMethod::Shorter,
+            Easing::Linear
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn easing() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(ease(0., easing), 0.);
+            assert_eq!(ease(1., easing), 1.);
+        }
+        assert_eq!(ease(0.5, Easing::EaseInOut), 0.5);
+    }
+}
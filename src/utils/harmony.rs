@@ -0,0 +1,49 @@
+use crate::color_print::{ColorStandered, Harmony, NumType};
+use crate::utils::interpolate;
+
+/// The hue offsets in degrees making up `harmony`, starting with the color itself.
+pub fn angles(harmony: Harmony) -> &'static [NumType] {
+    match harmony {
+        Harmony::Complementary => &[0., 180.],
+        Harmony::SplitComplementary => &[0., 150., 210.],
+        Harmony::Analogous => &[0., 30., 330.],
+        Harmony::Triadic => &[0., 120., 240.],
+        Harmony::Tetradic => &[0., 60., 180., 240.],
+        Harmony::Square => &[0., 90., 180., 270.],
+    }
+}
+
+/// Rotates the hue of `components` in `standered` by `degrees`. Returns `None` for a standered
+/// without a hue.
+pub fn rotate_hue(
+    components: (NumType, NumType, NumType, Option<NumType>),
+    standered: ColorStandered,
+    degrees: NumType,
+) -> Option<(NumType, NumType, NumType, Option<NumType>)> {
+    let hue = interpolate::hue_index(standered)?;
+    let mut values = [components.0, components.1, components.2];
+    values[hue] = (values[hue] + degrees).rem_euclid(360.);
+
+    Some((values[0], values[1], values[2], components.3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation() {
+        assert_eq!(
+            rotate_hue((300., 0.5, 0.5, None), ColorStandered::Hsl, 90.),
+            Some((30., 0.5, 0.5, None))
+        );
+        assert_eq!(
+            rotate_hue((0.5, 0.1, 10., None), ColorStandered::Oklch, -30.),
+            Some((0.5, 0.1, 340., None))
+        );
+        assert_eq!(
+            rotate_hue((1., 2., 3., None), ColorStandered::Rgb, 90.),
+            None
+        );
+    }
+}
@@ -1,9 +1,11 @@
+use crate::color_print::WhitePoint;
+
 pub fn max<T: std::cmp::PartialOrd>(v1: T, v2: T, v3: T) -> T {
     if v1 == v2 {
         if v1 > v3 {
             v1
         } else {
-            v2
+            v3
         }
     } else if v2 == v3 {
         if v2 > v1 {
@@ -53,3 +55,236 @@ pub fn min<T: std::cmp::PartialOrd>(v1: T, v2: T, v3: T) -> T {
         v3
     }
 }
+
+pub const XYZ_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [3.2409699419045226, -1.537383177570094, -0.4986107602930034],
+    [-0.9692436362808796, 1.8759675015077202, 0.04155505740717559],
+    [
+        0.05563007969699366,
+        -0.20397695888897652,
+        1.0569715142428786,
+    ],
+];
+
+pub const LINEAR_SRGB_TO_XYZ: [[f64; 3]; 3] = [
+    [0.41239079926595934, 0.357584339383878, 0.1804807884018343],
+    [0.21263900587151027, 0.715168678767756, 0.07219231536073371],
+    [0.01933081871559182, 0.11919477979462598, 0.9505321522496607],
+];
+
+pub const LINEAR_DISPLAY_P3_TO_XYZ: [[f64; 3]; 3] = [
+    [0.4865709486482162, 0.26566769316909306, 0.1982172852343625],
+    [0.2289745640697488, 0.6917385218365064, 0.079286914093745],
+    [0., 0.04511338185890264, 1.043944368900976],
+];
+
+pub const LINEAR_SRGB_TO_LMS: [[f64; 3]; 3] = [
+    [0.4122214708, 0.5363313004, 0.0514459929],
+    [0.2119034982, 0.6806995451, 0.1073969566],
+    [0.0883024619, 0.2817188376, 0.6299787005],
+];
+
+pub const LMS_TO_OKLAB: [[f64; 3]; 3] = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+
+/// The inverse of `LMS_TO_OKLAB`, computed with `invert_matrix` since the published one is too
+/// imprecise to round trip.
+pub const OKLAB_TO_LMS: [[f64; 3]; 3] = [
+    [0.9999999984505199, 0.39633779217376786, 0.2158037580607588],
+    [
+        1.0000000088817607,
+        -0.10556134232365634,
+        -0.0638541747717059,
+    ],
+    [1.000000054672411, -0.08948418209496577, -1.291485537864092],
+];
+
+/// The inverse of `LINEAR_SRGB_TO_LMS`, computed with `invert_matrix`.
+pub const LMS_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [4.076735270402869, -3.3076984413096295, 0.23096820901311144],
+    [-1.2684360156125638, 2.609753309450898, -0.3413188612374302],
+    [
+        -0.004196079963799717,
+        -0.7034186279934823,
+        1.707614702701005,
+    ],
+];
+
+pub const D50_TO_D65: [[f64; 3]; 3] = [
+    [0.955473421488075, -0.02309845494876471, 0.06325924320057072],
+    [
+        -0.0283697093338637,
+        1.0099953980813041,
+        0.021041441191917323,
+    ],
+    [
+        0.012314014864481998,
+        -0.020507649298898964,
+        1.330365926242124,
+    ],
+];
+
+pub const D65_TO_D50: [[f64; 3]; 3] = [
+    [
+        1.0479297925449969,
+        0.022946870601609652,
+        -0.05019226628920524,
+    ],
+    [
+        0.02962780877005599,
+        0.9904344267538799,
+        -0.017073799063418826,
+    ],
+    [
+        -0.009243040646204504,
+        0.015055191490298152,
+        0.7518742814281371,
+    ],
+];
+
+pub const D50_WHITE: (f64, f64, f64) = (0.3457 / 0.3585, 1., (1. - 0.3457 - 0.3585) / 0.3585);
+pub const D65_WHITE: (f64, f64, f64) = (0.3127 / 0.3290, 1., (1. - 0.3127 - 0.3290) / 0.3290);
+
+pub fn multiply_matrix(matrix: &[[f64; 3]; 3], vector: (f64, f64, f64)) -> (f64, f64, f64) {
+    let row =
+        |i: usize| matrix[i][0] * vector.0 + matrix[i][1] * vector.1 + matrix[i][2] * vector.2;
+
+    (row(0), row(1), row(2))
+}
+
+/// Inverts a matrix, used to derive the inverse matrices above.
+pub fn invert_matrix(matrix: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let [[a, b, c], [d, e, f], [g, h, i]] = *matrix;
+    let determinant = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+
+    [
+        [
+            (e * i - f * h) / determinant,
+            (c * h - b * i) / determinant,
+            (b * f - c * e) / determinant,
+        ],
+        [
+            (f * g - d * i) / determinant,
+            (a * i - c * g) / determinant,
+            (c * d - a * f) / determinant,
+        ],
+        [
+            (d * h - e * g) / determinant,
+            (b * g - a * h) / determinant,
+            (a * e - b * d) / determinant,
+        ],
+    ]
+}
+
+/// Unpacks a `0xRRGGBB` value.
+pub fn unpack_rgb(value: u32) -> (f64, f64, f64) {
+    (
+        (value >> 16 & 0xff) as f64,
+        (value >> 8 & 0xff) as f64,
+        (value & 0xff) as f64,
+    )
+}
+
+/// Decodes a gamma encoded sRGB channel in `0..=1` into linear light.
+pub fn srgb_to_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+
+    if abs <= 0.04045 {
+        channel / 12.92
+    } else {
+        channel.signum() * ((abs + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes a linear light channel into gamma encoded sRGB in `0..=1`.
+pub fn linear_to_srgb(channel: f64) -> f64 {
+    let abs = channel.abs();
+
+    if abs <= 0.04045 / 12.92 {
+        channel * 12.92
+    } else {
+        channel.signum() * (1.055 * abs.powf(1. / 2.4) - 0.055)
+    }
+}
+
+const LAB_KAPPA: f64 = 24389. / 27.;
+const LAB_EPSILON: f64 = 216. / 24389.;
+
+pub fn xyz_to_lab(xyz: (f64, f64, f64), white: (f64, f64, f64)) -> (f64, f64, f64) {
+    let f = |t: f64| {
+        if t > LAB_EPSILON {
+            t.cbrt()
+        } else {
+            (LAB_KAPPA * t + 16.) / 116.
+        }
+    };
+    let fx = f(xyz.0 / white.0);
+    let fy = f(xyz.1 / white.1);
+    let fz = f(xyz.2 / white.2);
+
+    (116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz))
+}
+
+pub fn lab_to_xyz(lab: (f64, f64, f64), white: (f64, f64, f64)) -> (f64, f64, f64) {
+    let f1 = (lab.0 + 16.) / 116.;
+    let f0 = lab.1 / 500. + f1;
+    let f2 = f1 - lab.2 / 200.;
+
+    let x = if f0.powi(3) > LAB_EPSILON {
+        f0.powi(3)
+    } else {
+        (116. * f0 - 16.) / LAB_KAPPA
+    };
+    let y = if lab.0 > LAB_KAPPA * LAB_EPSILON {
+        f1.powi(3)
+    } else {
+        lab.0 / LAB_KAPPA
+    };
+    let z = if f2.powi(3) > LAB_EPSILON {
+        f2.powi(3)
+    } else {
+        (116. * f2 - 16.) / LAB_KAPPA
+    };
+
+    (x * white.0, y * white.1, z * white.2)
+}
+
+/// Converts a rectangular `(lightness, a, b)` triple into its cylindrical `(lightness, chroma, hue)` form.
+pub fn to_polar(lab: (f64, f64, f64)) -> (f64, f64, f64) {
+    let chroma = (lab.1 * lab.1 + lab.2 * lab.2).sqrt();
+    let hue = lab.2.atan2(lab.1).to_degrees().rem_euclid(360.);
+
+    (lab.0, chroma, hue)
+}
+
+pub fn from_polar(lch: (f64, f64, f64)) -> (f64, f64, f64) {
+    let hue = lch.2.to_radians();
+
+    (lch.0, lch.1 * hue.cos(), lch.1 * hue.sin())
+}
+
+pub fn white_point(white_point: WhitePoint) -> (f64, f64, f64) {
+    match white_point {
+        WhitePoint::D50 => D50_WHITE,
+        WhitePoint::D65 => D65_WHITE,
+    }
+}
+
+/// Converts CIE XYZ relative to D65 into L*a*b* relative to `white_point`.
+pub fn xyz_d65_to_lab(xyz: (f64, f64, f64), white_point: WhitePoint) -> (f64, f64, f64) {
+    match white_point {
+        WhitePoint::D50 => xyz_to_lab(multiply_matrix(&D65_TO_D50, xyz), D50_WHITE),
+        WhitePoint::D65 => xyz_to_lab(xyz, D65_WHITE),
+    }
+}
+
+/// Converts L*a*b* relative to `white_point` into CIE XYZ relative to D65.
+pub fn lab_to_xyz_d65(lab: (f64, f64, f64), white_point: WhitePoint) -> (f64, f64, f64) {
+    match white_point {
+        WhitePoint::D50 => multiply_matrix(&D50_TO_D65, lab_to_xyz(lab, D50_WHITE)),
+        WhitePoint::D65 => lab_to_xyz(lab, D65_WHITE),
+    }
+}
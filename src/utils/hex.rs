@@ -0,0 +1,73 @@
+use crate::color_print::{Exeptions, NumType};
+use crate::utils::types::*;
+
+pub struct ParsedHex {
+    pub rgb: RGB,
+    pub alpha: NumType,
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`, with or without the leading `#`.
+///
+/// Errors report the byte position of the offending digit in `value`.
+pub fn parse(value: &str) -> Result<ParsedHex, Exeptions> {
+    let offset = usize::from(value.starts_with('#'));
+    let digits = &value[offset..];
+
+    let mut nibbles = Vec::with_capacity(8);
+    for (position, character) in digits.char_indices() {
+        let nibble = character
+            .to_digit(16)
+            .ok_or(Exeptions::InvalidHexDigit((position + offset) as u32))?;
+        nibbles.push(nibble as u8);
+    }
+
+    let channels: Vec<u8> = match nibbles.len() {
+        3 | 4 => nibbles.iter().map(|nibble| nibble * 17).collect(),
+        6 | 8 => nibbles
+            .chunks(2)
+            .map(|pair| pair[0] * 16 + pair[1])
+            .collect(),
+        len => return Err(Exeptions::IncorrectLength(len as u32)),
+    };
+
+    Ok(ParsedHex {
+        rgb: (
+            channels[0] as NumType,
+            channels[1] as NumType,
+            channels[2] as NumType,
+        ),
+        alpha: channels.get(3).map_or(1., |alpha| *alpha as NumType / 255.),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(value: &str) -> (RGB, NumType) {
+        let hex = parse(value).unwrap();
+        (hex.rgb, hex.alpha)
+    }
+
+    #[test]
+    fn lengths() {
+        assert_eq!(parsed("#fa0"), ((255., 170., 0.), 1.));
+        assert_eq!(parsed("FA08"), ((255., 170., 0.), 136. / 255.));
+        assert_eq!(parsed("#0a141e"), ((10., 20., 30.), 1.));
+        assert_eq!(parsed("0A141Eff"), ((10., 20., 30.), 1.));
+        assert_eq!(parsed("#0a141e00"), ((10., 20., 30.), 0.));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            parse("#12345"),
+            Err(Exeptions::IncorrectLength(5))
+        ));
+        assert!(matches!(parse(""), Err(Exeptions::IncorrectLength(0))));
+        assert!(matches!(parse("#12g"), Err(Exeptions::InvalidHexDigit(3))));
+        assert!(matches!(parse("12g"), Err(Exeptions::InvalidHexDigit(2))));
+        assert!(matches!(parse("#é12"), Err(Exeptions::InvalidHexDigit(1))));
+        assert!(matches!(parse("##123"), Err(Exeptions::InvalidHexDigit(1))));
+    }
+}
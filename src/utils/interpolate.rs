@@ -0,0 +1,214 @@
+use crate::color_print::{ColorStandered, HueMethod, NumType};
+
+type Components = (NumType, NumType, NumType, Option<NumType>);
+
+/// Where the hue sits in the components of a cylindrical standered.
+pub fn hue_index(standered: ColorStandered) -> Option<usize> {
+    match standered {
+        ColorStandered::Hsl | ColorStandered::Hsv => Some(0),
+        ColorStandered::Lch | ColorStandered::Oklch => Some(2),
+        _ => None,
+    }
+}
+
+/// Interpolates between two hues in degrees, going the way `method` picks.
+pub fn interpolate_hue(from: NumType, to: NumType, t: NumType, method: HueMethod) -> NumType {
+    let from = from.rem_euclid(360.);
+    let mut to = to.rem_euclid(360.);
+    let difference = to - from;
+
+    match method {
+        HueMethod::Shorter if difference > 180. => to -= 360.,
+        HueMethod::Shorter if difference < -180. => to += 360.,
+        HueMethod::Longer if (0. ..=180.).contains(&difference) => to -= 360.,
+        HueMethod::Longer if (-180. ..0.).contains(&difference) => to += 360.,
+        HueMethod::Increasing if difference < 0. => to += 360.,
+        HueMethod::Decreasing if difference > 0. => to -= 360.,
+        _ => {}
+    }
+
+    (from + (to - from) * t).rem_euclid(360.)
+}
+
+/// Interpolates the components of two colors in `standered`, as returned by `stored_color`.
+///
+/// The hue of a color without saturation or chroma is meaningless, so the other color's hue is
+/// used for it instead.
+pub fn interpolate(
+    from: Components,
+    to: Components,
+    t: NumType,
+    standered: ColorStandered,
+    method: HueMethod,
+) -> Components {
+    let lerp = |from: NumType, to: NumType| from + (to - from) * t;
+    let mut from = [from.0, from.1, from.2, from.3.unwrap_or_default()];
+    let mut to = [to.0, to.1, to.2, to.3.unwrap_or_default()];
+    let fourth = (standered == ColorStandered::Cmyk).then_some(0.);
+
+    let Some(hue) = hue_index(standered) else {
+        return (
+            lerp(from[0], to[0]),
+            lerp(from[1], to[1]),
+            lerp(from[2], to[2]),
+            fourth.map(|_| lerp(from[3], to[3])),
+        );
+    };
+
+    // Saturation and chroma both sit in the second component.
+    if from[1].abs() < 1e-9 {
+        from[hue] = to[hue];
+    } else if to[1].abs() < 1e-9 {
+        to[hue] = from[hue];
+    }
+
+    let mut result = [
+        lerp(from[0], to[0]),
+        lerp(from[1], to[1]),
+        lerp(from[2], to[2]),
+    ];
+    result[hue] = interpolate_hue(from[hue], to[hue], t, method);
+
+    (result[0], result[1], result[2], None)
+}
+
+/// Like `interpolate`, but with every component other than the hue premultiplied by alpha first,
+/// the way CSS interpolates translucent colors. Returns the components and the alpha.
+pub fn interpolate_premultiplied(
+    (from, from_alpha): (Components, NumType),
+    (to, to_alpha): (Components, NumType),
+    t: NumType,
+    standered: ColorStandered,
+    method: HueMethod,
+) -> (Components, NumType) {
+    let hue = hue_index(standered);
+    let scale = |components: Components, factor: NumType| {
+        let scale = |index: usize, value: NumType| {
+            if Some(index) == hue {
+                value
+            } else {
+                value * factor
+            }
+        };
+
+        (
+            scale(0, components.0),
+            scale(1, components.1),
+            scale(2, components.2),
+            components.3.map(|value| value * factor),
+        )
+    };
+
+    let alpha = from_alpha + (to_alpha - from_alpha) * t;
+    if alpha == 0. {
+        return (interpolate(from, to, t, standered, method), alpha);
+    }
+
+    let components = interpolate(
+        scale(from, from_alpha),
+        scale(to, to_alpha),
+        t,
+        standered,
+        method,
+    );
+
+    (scale(components, 1. / alpha), alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hues() {
+        assert_eq!(interpolate_hue(350., 10., 0.5, HueMethod::Shorter), 0.);
+        assert_eq!(interpolate_hue(350., 10., 0.5, HueMethod::Longer), 180.);
+        assert_eq!(interpolate_hue(10., 350., 0.25, HueMethod::Shorter), 5.);
+        assert_eq!(interpolate_hue(10., 350., 0.25, HueMethod::Longer), 95.);
+        assert_eq!(interpolate_hue(0., 90., 0.5, HueMethod::Longer), 225.);
+        assert_eq!(interpolate_hue(350., 10., 0.5, HueMethod::Increasing), 0.);
+        assert_eq!(interpolate_hue(10., 350., 0.5, HueMethod::Increasing), 180.);
+        assert_eq!(interpolate_hue(10., 350., 0.5, HueMethod::Decreasing), 0.);
+        assert_eq!(interpolate_hue(350., 10., 0.5, HueMethod::Decreasing), 180.);
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(
+            interpolate(
+                (0., 0., 0., None),
+                (255., 100., 50., None),
+                0.5,
+                ColorStandered::Rgb,
+                HueMethod::Shorter
+            ),
+            (127.5, 50., 25., None)
+        );
+        assert_eq!(
+            interpolate(
+                (0., 0., 0., Some(1.)),
+                (1., 1., 1., Some(0.)),
+                0.25,
+                ColorStandered::Cmyk,
+                HueMethod::Shorter
+            ),
+            (0.25, 0.25, 0.25, Some(0.75))
+        );
+        // A gray takes on the hue of the other color instead of sweeping through red.
+        assert_eq!(
+            interpolate(
+                (0., 0., 0.5, None),
+                (240., 1., 0.5, None),
+                0.5,
+                ColorStandered::Hsl,
+                HueMethod::Shorter
+            ),
+            (240., 0.5, 0.5, None)
+        );
+        assert_eq!(
+            interpolate(
+                (0.5, 0.1, 350., None),
+                (0.7, 0.2, 30., None),
+                0.5,
+                ColorStandered::Oklch,
+                HueMethod::Shorter
+            ),
+            (0.6, 0.15000000000000002, 10., None)
+        );
+    }
+
+    #[test]
+    fn premultiplied() {
+        // The opaque color dominates, as in CSS color-mix().
+        assert_eq!(
+            interpolate_premultiplied(
+                ((255., 0., 0., None), 1.),
+                ((0., 0., 255., None), 0.),
+                0.5,
+                ColorStandered::Rgb,
+                HueMethod::Shorter
+            ),
+            ((255., 0., 0., None), 0.5)
+        );
+        assert_eq!(
+            interpolate_premultiplied(
+                ((0., 1., 0.5, None), 1.),
+                ((120., 1., 0.5, None), 0.25),
+                0.5,
+                ColorStandered::Hsl,
+                HueMethod::Shorter
+            ),
+            ((60., 1., 0.5, None), 0.625)
+        );
+        assert_eq!(
+            interpolate_premultiplied(
+                ((255., 0., 0., None), 0.),
+                ((0., 0., 255., None), 0.),
+                0.5,
+                ColorStandered::Rgb,
+                HueMethod::Shorter
+            ),
+            ((127.5, 0., 127.5, None), 0.)
+        );
+    }
+}
@@ -1,9 +1,25 @@
 #![allow(unused)]
 
-mod helper;
-
+pub mod ansi;
+pub mod blend;
+pub mod colormap;
+pub mod composite;
+pub mod contrast;
+pub mod css;
+pub mod difference;
 pub mod from_rgb;
+pub mod gamut;
+pub mod gradient;
+pub mod harmony;
+pub mod helper;
+pub mod hex;
+pub mod interpolate;
+pub mod names;
+pub mod quantize;
+pub mod render;
+pub mod terminal;
 pub mod to_rgb;
+pub mod value;
 
 pub mod types {
     mod typedef;
@@ -0,0 +1,212 @@
+use crate::color_print::{ColorDepth, NumType};
+use crate::utils::from_rgb::rgb_to_oklab;
+use crate::utils::types::*;
+
+/// The xterm defaults for the 16 base colors.
+pub const ANSI_16: [RGB; 16] = [
+    (0., 0., 0.),
+    (205., 0., 0.),
+    (0., 205., 0.),
+    (205., 205., 0.),
+    (0., 0., 238.),
+    (205., 0., 205.),
+    (0., 205., 205.),
+    (229., 229., 229.),
+    (127., 127., 127.),
+    (255., 0., 0.),
+    (0., 255., 0.),
+    (255., 255., 0.),
+    (92., 92., 255.),
+    (255., 0., 255.),
+    (0., 255., 255.),
+    (255., 255., 255.),
+];
+
+/// The channel levels of the xterm 6x6x6 color cube.
+const CUBE_LEVELS: [NumType; 6] = [0., 95., 135., 175., 215., 255.];
+
+/// The squared OKLab distance, which is what "nearest" means throughout this module.
+fn distance(rgb1: RGB, rgb2: RGB) -> NumType {
+    let lab1 = rgb_to_oklab(rgb1.0, rgb1.1, rgb1.2);
+    let lab2 = rgb_to_oklab(rgb2.0, rgb2.1, rgb2.2);
+
+    (lab1.0 - lab2.0).powi(2) + (lab1.1 - lab2.1).powi(2) + (lab1.2 - lab2.2).powi(2)
+}
+
+fn nearest(rgb: RGB, candidates: impl Iterator<Item = (u8, RGB)>) -> u8 {
+    candidates
+        .map(|(index, candidate)| (index, distance(rgb, candidate)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(index, _)| index)
+}
+
+/// The RGB value of an xterm 256 color palette index.
+pub fn xterm_256_rgb(index: u8) -> RGB {
+    match index {
+        0..=15 => ANSI_16[index as usize],
+        16..=231 => {
+            let index = index as usize - 16;
+            (
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[index / 6 % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        }
+        _ => {
+            let level = 8. + 10. * (index - 232) as NumType;
+            (level, level, level)
+        }
+    }
+}
+
+/// The nearest color in the 6x6x6 cube or the grayscale ramp of the xterm 256 color palette.
+///
+/// The first 16 entries are left out since terminals theme them freely.
+pub fn to_xterm_256(rgb: RGB) -> u8 {
+    let level = |channel: NumType| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by(|a, b| (a.1 - channel).abs().total_cmp(&(b.1 - channel).abs()))
+            .map_or(0, |(index, _)| index as u8)
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+
+    let gray = (rgb.0 + rgb.1 + rgb.2) / 3.;
+    let gray = 232 + ((gray - 8.) / 10.).round().clamp(0., 23.) as u8;
+
+    nearest(
+        rgb,
+        [cube, gray]
+            .into_iter()
+            .map(|index| (index, xterm_256_rgb(index))),
+    )
+}
+
+/// The nearest of the first `count` base colors, for `count` of 8 or 16.
+pub fn to_ansi(rgb: RGB, count: usize) -> u8 {
+    nearest(
+        rgb,
+        ANSI_16[..count]
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| (index as u8, *candidate)),
+    )
+}
+
+/// Which part of the text an SGR color applies to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Layer {
+    Foreground,
+    Background,
+    Underline,
+}
+
+/// The SGR parameters selecting `rgb` for `layer`. Returns `None` for `ColorDepth::None`.
+///
+/// Underlines only have extended color forms, so 16 and 8 color depths fall back to the matching
+/// index of the 256 color palette.
+pub fn sgr_parameters(rgb: RGB, depth: ColorDepth, layer: Layer) -> Option<String> {
+    let (extended, base, bright) = match layer {
+        Layer::Foreground => (38, Some(30), 90),
+        Layer::Background => (48, Some(40), 100),
+        Layer::Underline => (58, None, 0),
+    };
+
+    match (depth, base) {
+        // Clamped so colors just outside sRGB never print as 256 or -0.
+        (ColorDepth::Truecolor, _) => Some(format!(
+            "{};2;{};{};{}",
+            extended,
+            rgb.0.clamp(0., 255.) as u8,
+            rgb.1.clamp(0., 255.) as u8,
+            rgb.2.clamp(0., 255.) as u8
+        )),
+        (ColorDepth::Xterm256, _) => Some(format!("{};5;{}", extended, to_xterm_256(rgb))),
+        (ColorDepth::Ansi16, Some(base)) => match to_ansi(rgb, 16) {
+            index @ 0..=7 => Some(format!("{}", base + index)),
+            index => Some(format!("{}", bright + index - 8)),
+        },
+        (ColorDepth::Ansi8, Some(base)) => Some(format!("{}", base + to_ansi(rgb, 8))),
+        (ColorDepth::Ansi16, None) => Some(format!("{};5;{}", extended, to_ansi(rgb, 16))),
+        (ColorDepth::Ansi8, None) => Some(format!("{};5;{}", extended, to_ansi(rgb, 8))),
+        (ColorDepth::None, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xterm_256() {
+        assert_eq!(to_xterm_256((0., 0., 0.)), 16);
+        assert_eq!(to_xterm_256((255., 255., 255.)), 231);
+        assert_eq!(to_xterm_256((255., 0., 0.)), 196);
+        assert_eq!(to_xterm_256((95., 135., 175.)), 67);
+        assert_eq!(to_xterm_256((128., 128., 128.)), 244);
+        assert_eq!(to_xterm_256((10., 10., 12.)), 232);
+
+        for index in 16..=255 {
+            assert_eq!(to_xterm_256(xterm_256_rgb(index)), index);
+        }
+    }
+
+    #[test]
+    fn ansi() {
+        assert_eq!(to_ansi((250., 10., 10.), 16), 9);
+        assert_eq!(to_ansi((250., 10., 10.), 8), 1);
+        assert_eq!(to_ansi((20., 20., 20.), 16), 0);
+        assert_eq!(to_ansi((240., 240., 240.), 8), 7);
+    }
+
+    #[test]
+    fn parameters() {
+        let red = (250., 10., 10.);
+
+        assert_eq!(
+            sgr_parameters((10.2, 20.3, 30.4), ColorDepth::Truecolor, Layer::Foreground),
+            Some("38;2;10;20;30".to_string())
+        );
+        assert_eq!(
+            sgr_parameters((293.4, -0.2, 0.), ColorDepth::Truecolor, Layer::Foreground),
+            Some("38;2;255;0;0".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Xterm256, Layer::Background),
+            Some("48;5;196".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Ansi16, Layer::Foreground),
+            Some("91".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Ansi16, Layer::Background),
+            Some("101".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Ansi8, Layer::Background),
+            Some("41".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::None, Layer::Foreground),
+            None
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Truecolor, Layer::Underline),
+            Some("58;2;250;10;10".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Xterm256, Layer::Underline),
+            Some("58;5;196".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Ansi16, Layer::Underline),
+            Some("58;5;9".to_string())
+        );
+        assert_eq!(
+            sgr_parameters(red, ColorDepth::Ansi8, Layer::Underline),
+            Some("58;5;1".to_string())
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use crate::color_print::{NumType, UnderlineStyle};
+use crate::utils::types::*;
+use crate::Style as StyleStruct;
+use unicode_width::UnicodeWidthStr;
+
+const FONT_SIZE: NumType = 14.;
+const CELL_WIDTH: NumType = 8.4;
+const LINE_HEIGHT: NumType = 18.;
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Rounds away float noise, like `25.200000000000003` for three cells, in coordinates.
+fn length(value: NumType) -> NumType {
+    (value * 100.).round() / 100.
+}
+
+fn css_rgb(rgb: RGB) -> String {
+    format!(
+        "rgb({}, {}, {})",
+        rgb.0.round(),
+        rgb.1.round(),
+        rgb.2.round()
+    )
+}
+
+/// The foreground and background of `style`, after applying reverse video. Reversing a missing
+/// color uses the default foreground (palette entry 7) or background (palette entry 0).
+fn colors(style: &StyleStruct, palette: &[RGB; 16]) -> (Option<RGB>, Option<RGB>) {
+    let foreground = style.foreground.as_ref().map(|color| color.rgb_value());
+    let background = style.background.as_ref().map(|color| color.rgb_value());
+
+    if style.reverse {
+        (
+            Some(background.unwrap_or(palette[0])),
+            Some(foreground.unwrap_or(palette[7])),
+        )
+    } else {
+        (foreground, background)
+    }
+}
+
+fn decoration(style: &StyleStruct) -> Option<(&'static str, Option<&'static str>)> {
+    let line = match (style.underline, style.strikethrough) {
+        (UnderlineStyle::None, false) => return None,
+        (UnderlineStyle::None, true) => "line-through",
+        (_, false) => "underline",
+        (_, true) => "underline line-through",
+    };
+    let kind = match style.underline {
+        UnderlineStyle::Double => Some("double"),
+        UnderlineStyle::Curly => Some("wavy"),
+        UnderlineStyle::Dotted => Some("dotted"),
+        UnderlineStyle::Dashed => Some("dashed"),
+        _ => None,
+    };
+
+    Some((line, kind))
+}
+
+/// Renders parsed runs as HTML `<span>` elements with inline styles. Blink is not rendered.
+pub fn to_html(runs: &[(String, StyleStruct)], palette: &[RGB; 16]) -> String {
+    let mut html = String::new();
+
+    for (text, style) in runs {
+        let (foreground, background) = colors(style, palette);
+        let mut properties = Vec::new();
+
+        if let Some(foreground) = foreground {
+            properties.push(format!("color: {}", css_rgb(foreground)));
+        }
+        if let Some(background) = background {
+            properties.push(format!("background-color: {}", css_rgb(background)));
+        }
+        if style.bold {
+            properties.push("font-weight: bold".to_string());
+        }
+        if style.dim {
+            properties.push("opacity: 0.5".to_string());
+        }
+        if style.italic {
+            properties.push("font-style: italic".to_string());
+        }
+        if let Some((line, kind)) = decoration(style) {
+            properties.push(format!("text-decoration-line: {}", line));
+            if let Some(kind) = kind {
+                properties.push(format!("text-decoration-style: {}", kind));
+            }
+        }
+        if let Some(underline_color) = &style.underline_color {
+            properties.push(format!(
+                "text-decoration-color: {}",
+                css_rgb(underline_color.rgb_value())
+            ));
+        }
+        if style.hidden {
+            properties.push("visibility: hidden".to_string());
+        }
+
+        if properties.is_empty() {
+            html.push_str(&escape(text));
+        } else {
+            html.push_str(&format!(
+                "<span style=\"{}\">{}</span>",
+                properties.join("; "),
+                escape(text)
+            ));
+        }
+    }
+
+    html
+}
+
+/// Renders parsed runs as a standalone SVG document on a monospace grid, with palette entries 7
+/// and 0 as the default foreground and background. Blink is not rendered.
+pub fn to_svg(runs: &[(String, StyleStruct)], palette: &[RGB; 16]) -> String {
+    let mut lines: Vec<Vec<(&str, &StyleStruct)>> = vec![Vec::new()];
+    for (text, style) in runs {
+        for (index, part) in text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap().push((part, style));
+            }
+        }
+    }
+
+    let columns = lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|(text, _)| UnicodeWidthStr::width(*text))
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(0);
+    let width = length(columns as NumType * CELL_WIDTH);
+    let height = length(lines.len() as NumType * LINE_HEIGHT);
+
+    let mut backgrounds = String::new();
+    let mut texts = String::new();
+
+    for (row, line) in lines.iter().enumerate() {
+        let y = row as NumType * LINE_HEIGHT;
+        let mut column = 0;
+        let mut spans = String::new();
+
+        for (text, style) in line {
+            let count = UnicodeWidthStr::width(*text);
+            let (foreground, background) = colors(style, palette);
+
+            if let Some(background) = background {
+                backgrounds.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    length(column as NumType * CELL_WIDTH),
+                    y,
+                    length(count as NumType * CELL_WIDTH),
+                    LINE_HEIGHT,
+                    css_rgb(background)
+                ));
+            }
+
+            let mut attributes = format!(" x=\"{}\"", length(column as NumType * CELL_WIDTH));
+            if let Some(foreground) = foreground {
+                attributes.push_str(&format!(" fill=\"{}\"", css_rgb(foreground)));
+            }
+            if style.bold {
+                attributes.push_str(" font-weight=\"bold\"");
+            }
+            if style.dim {
+                attributes.push_str(" opacity=\"0.5\"");
+            }
+            if style.italic {
+                attributes.push_str(" font-style=\"italic\"");
+            }
+            if let Some((line, _)) = decoration(style) {
+                attributes.push_str(&format!(" text-decoration=\"{}\"", line));
+            }
+            if style.hidden {
+                attributes.push_str(" visibility=\"hidden\"");
+            }
+
+            spans.push_str(&format!("<tspan{}>{}</tspan>", attributes, escape(text)));
+            column += count;
+        }
+
+        if !spans.is_empty() {
+            texts.push_str(&format!(
+                "<text y=\"{}\">{}</text>",
+                y + LINE_HEIGHT - 4.,
+                spans
+            ));
+        }
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"{}\" \
+         xml:space=\"preserve\">\
+         <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\
+         {backgrounds}<g fill=\"{}\">{texts}</g></svg>",
+        FONT_SIZE,
+        css_rgb(palette[0]),
+        css_rgb(palette[7]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ansi::parse;
+    use crate::utils::quantize::ANSI_16;
+
+    #[test]
+    fn html() {
+        let runs = parse("a<b\x1b[1;31mred\x1b[0m\x1b[4:3;58;5;9mx\x1b[7my", &ANSI_16);
+
+        assert_eq!(
+            to_html(&runs, &ANSI_16),
+            "a&lt;b\
+             <span style=\"color: rgb(205, 0, 0); font-weight: bold\">red</span>\
+             <span style=\"text-decoration-line: underline; text-decoration-style: wavy; \
+             text-decoration-color: rgb(255, 0, 0)\">x</span>\
+             <span style=\"color: rgb(0, 0, 0); background-color: rgb(229, 229, 229); \
+             text-decoration-line: underline; text-decoration-style: wavy; \
+             text-decoration-color: rgb(255, 0, 0)\">y</span>"
+        );
+    }
+
+    #[test]
+    fn palette() {
+        let mut palette = ANSI_16;
+        palette[1] = (1., 2., 3.);
+        let runs = parse("\x1b[31mred", &palette);
+
+        assert_eq!(
+            to_html(&runs, &palette),
+            "<span style=\"color: rgb(1, 2, 3)\">red</span>"
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let runs = parse("ab\x1b[41mc\nd\x1b[0m", &ANSI_16);
+
+        assert_eq!(
+            to_svg(&runs, &ANSI_16),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"25.2\" height=\"36\" \
+             viewBox=\"0 0 25.2 36\" font-family=\"monospace\" font-size=\"14\" \
+             xml:space=\"preserve\">\
+             <rect width=\"100%\" height=\"100%\" fill=\"rgb(0, 0, 0)\"/>\
+             <rect x=\"16.8\" y=\"0\" width=\"8.4\" height=\"18\" fill=\"rgb(205, 0, 0)\"/>\
+             <rect x=\"0\" y=\"18\" width=\"8.4\" height=\"18\" fill=\"rgb(205, 0, 0)\"/>\
+             <g fill=\"rgb(229, 229, 229)\">\
+             <text y=\"14\"><tspan x=\"0\">ab</tspan><tspan x=\"16.8\">c</tspan></text>\
+             <text y=\"32\"><tspan x=\"0\">d</tspan></text></g></svg>"
+        );
+    }
+
+    #[test]
+    fn svg_wide_characters() {
+        let runs = parse("\u{6f22}\u{5b57}\x1b[41mx", &ANSI_16);
+        let svg = to_svg(&runs, &ANSI_16);
+
+        assert!(svg.contains("width=\"42\""));
+        assert!(svg.contains("<rect x=\"33.6\" y=\"0\" width=\"8.4\""));
+        assert!(svg.contains("<tspan x=\"33.6\">x</tspan>"));
+    }
+}
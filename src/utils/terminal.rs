@@ -0,0 +1,144 @@
+use crate::color_print::ColorDepth;
+use std::io::IsTerminal;
+
+/// Picks the output depth from the process environment and whether stdout is a terminal.
+///
+/// wasm32-unknown-unknown has neither, so there the host is trusted to render truecolor; callers
+/// on that target that want to honor `NO_COLOR` have to pass their own depth.
+pub fn detect_depth() -> ColorDepth {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        return ColorDepth::Truecolor;
+    }
+
+    depth_from(
+        |name| std::env::var(name).ok(),
+        std::io::stdout().is_terminal(),
+    )
+}
+
+/// Picks the output depth, looking variables up through `var`.
+///
+/// In order of precedence: `FORCE_COLOR` (`0` or `false` disables color, `1`, `2` and `3` pick
+/// 16 colors, 256 colors and truecolor, anything else forces the detected depth), `NO_COLOR`,
+/// `CLICOLOR_FORCE`, whether stdout is a terminal, `CLICOLOR=0` and `TERM=dumb`. The depth itself
+/// comes from `COLORTERM` and `TERM`.
+pub fn depth_from(var: impl Fn(&str) -> Option<String>, is_tty: bool) -> ColorDepth {
+    let set = |name: &str| var(name).filter(|value| !value.is_empty());
+    let term = set("TERM").unwrap_or_default();
+
+    if let Some(force) = set("FORCE_COLOR") {
+        return match force.as_str() {
+            "0" | "false" => ColorDepth::None,
+            "1" => ColorDepth::Ansi16,
+            "2" => ColorDepth::Xterm256,
+            "3" => ColorDepth::Truecolor,
+            _ => supported_depth(&var, &term),
+        };
+    }
+
+    if set("NO_COLOR").is_some() {
+        return ColorDepth::None;
+    }
+
+    if set("CLICOLOR_FORCE").is_some_and(|force| force != "0") {
+        return supported_depth(&var, &term);
+    }
+
+    if !is_tty || set("CLICOLOR").as_deref() == Some("0") || term == "dumb" {
+        return ColorDepth::None;
+    }
+
+    supported_depth(&var, &term)
+}
+
+fn supported_depth(var: &impl Fn(&str) -> Option<String>, term: &str) -> ColorDepth {
+    let colorterm = var("COLORTERM").unwrap_or_default().to_lowercase();
+
+    if colorterm == "truecolor"
+        || colorterm == "24bit"
+        || term.ends_with("-direct")
+        || term.contains("truecolor")
+        || term.contains("24bit")
+    {
+        ColorDepth::Truecolor
+    } else if term.ends_with("256color") || term.ends_with("256") {
+        ColorDepth::Xterm256
+    } else if term == "ansi" {
+        ColorDepth::Ansi8
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(vars: &[(&str, &str)], is_tty: bool) -> ColorDepth {
+        depth_from(
+            |name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            is_tty,
+        )
+    }
+
+    #[test]
+    fn depths() {
+        assert_eq!(
+            depth(&[("COLORTERM", "truecolor")], true),
+            ColorDepth::Truecolor
+        );
+        assert_eq!(
+            depth(&[("TERM", "xterm-direct")], true),
+            ColorDepth::Truecolor
+        );
+        assert_eq!(
+            depth(&[("TERM", "xterm-256color")], true),
+            ColorDepth::Xterm256
+        );
+        assert_eq!(depth(&[("TERM", "xterm")], true), ColorDepth::Ansi16);
+        assert_eq!(depth(&[("TERM", "ansi")], true), ColorDepth::Ansi8);
+        assert_eq!(depth(&[], true), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn disabled() {
+        assert_eq!(
+            depth(&[("COLORTERM", "truecolor")], false),
+            ColorDepth::None
+        );
+        assert_eq!(depth(&[("TERM", "dumb")], true), ColorDepth::None);
+        assert_eq!(
+            depth(&[("NO_COLOR", "1"), ("TERM", "xterm")], true),
+            ColorDepth::None
+        );
+        assert_eq!(depth(&[("CLICOLOR", "0")], true), ColorDepth::None);
+        assert_eq!(depth(&[("FORCE_COLOR", "0")], true), ColorDepth::None);
+        assert_eq!(depth(&[("NO_COLOR", "")], true), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn forced() {
+        assert_eq!(depth(&[("FORCE_COLOR", "3")], false), ColorDepth::Truecolor);
+        assert_eq!(
+            depth(&[("FORCE_COLOR", "2"), ("NO_COLOR", "1")], false),
+            ColorDepth::Xterm256
+        );
+        assert_eq!(
+            depth(
+                &[("FORCE_COLOR", "true"), ("TERM", "xterm-256color")],
+                false
+            ),
+            ColorDepth::Xterm256
+        );
+        assert_eq!(depth(&[("CLICOLOR_FORCE", "1")], false), ColorDepth::Ansi16);
+        assert_eq!(
+            depth(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")], false),
+            ColorDepth::None
+        );
+        assert_eq!(depth(&[("CLICOLOR_FORCE", "0")], false), ColorDepth::None);
+    }
+}
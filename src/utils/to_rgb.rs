@@ -1,4 +1,8 @@
+use crate::color_print::WhitePoint;
+use crate::utils::helper::*;
+
 pub fn hsl_to_rgb(hue: f64, sateration: f64, lightness: f64) -> (f64, f64, f64) {
+    let hue = hue % 360.;
     let c = (1. - (2. * lightness - 1.).abs()) * sateration;
     let x = c * (1. - ((hue / 60.) % 2. - 1.).abs());
     let m = lightness - c / 2.;
@@ -23,6 +27,7 @@ pub fn hsl_to_rgb(hue: f64, sateration: f64, lightness: f64) -> (f64, f64, f64)
 }
 
 pub fn hsv_to_rgb(hue: f64, sateration: f64, value: f64) -> (f64, f64, f64) {
+    let hue = hue % 360.;
     let c = value * sateration;
     let x = c * (1. - ((hue / 60.) % 2. - 1.).abs());
     let m = value - c;
@@ -54,6 +59,82 @@ pub fn cmyk_to_rgb(cyan: f64, magenta: f64, yellow: f64, black: f64) -> (f64, f6
     )
 }
 
+pub fn hwb_to_rgb(hue: f64, whiteness: f64, blackness: f64) -> (f64, f64, f64) {
+    if whiteness + blackness >= 1. {
+        let gray = whiteness / (whiteness + blackness) * 255.;
+        return (gray, gray, gray);
+    }
+
+    let rgb = hsl_to_rgb(hue, 1., 0.5);
+    let scale = 1. - whiteness - blackness;
+
+    (
+        rgb.0 * scale + whiteness * 255.,
+        rgb.1 * scale + whiteness * 255.,
+        rgb.2 * scale + whiteness * 255.,
+    )
+}
+
+pub fn linear_srgb_to_rgb(red: f64, green: f64, blue: f64) -> (f64, f64, f64) {
+    (
+        linear_to_srgb(red) * 255.,
+        linear_to_srgb(green) * 255.,
+        linear_to_srgb(blue) * 255.,
+    )
+}
+
+/// Converts CIE XYZ relative to the D65 white point (`y` of white is 1) into sRGB.
+pub fn xyz_to_rgb(x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+    let linear = multiply_matrix(&XYZ_TO_LINEAR_SRGB, (x, y, z));
+
+    linear_srgb_to_rgb(linear.0, linear.1, linear.2)
+}
+
+/// Converts CIE XYZ relative to the D50 white point into sRGB using a Bradford adaptation.
+pub fn xyz_d50_to_rgb(x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+    let xyz = multiply_matrix(&D50_TO_D65, (x, y, z));
+
+    xyz_to_rgb(xyz.0, xyz.1, xyz.2)
+}
+
+pub fn display_p3_to_rgb(red: f64, green: f64, blue: f64) -> (f64, f64, f64) {
+    let linear = (
+        srgb_to_linear(red),
+        srgb_to_linear(green),
+        srgb_to_linear(blue),
+    );
+    let xyz = multiply_matrix(&LINEAR_DISPLAY_P3_TO_XYZ, linear);
+
+    xyz_to_rgb(xyz.0, xyz.1, xyz.2)
+}
+
+/// Converts CIE L*a*b* (D50, as used by CSS) into sRGB.
+pub fn lab_to_rgb(lightness: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let xyz = lab_to_xyz_d65((lightness, a, b), WhitePoint::D50);
+
+    xyz_to_rgb(xyz.0, xyz.1, xyz.2)
+}
+
+pub fn lch_to_rgb(lightness: f64, chroma: f64, hue: f64) -> (f64, f64, f64) {
+    let lab = from_polar((lightness, chroma, hue));
+
+    lab_to_rgb(lab.0, lab.1, lab.2)
+}
+
+pub fn oklab_to_rgb(lightness: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let lms = multiply_matrix(&OKLAB_TO_LMS, (lightness, a, b));
+    let lms = (lms.0.powi(3), lms.1.powi(3), lms.2.powi(3));
+    let linear = multiply_matrix(&LMS_TO_LINEAR_SRGB, lms);
+
+    linear_srgb_to_rgb(linear.0, linear.1, linear.2)
+}
+
+pub fn oklch_to_rgb(lightness: f64, chroma: f64, hue: f64) -> (f64, f64, f64) {
+    let lab = from_polar((lightness, chroma, hue));
+
+    oklab_to_rgb(lab.0, lab.1, lab.2)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -74,7 +155,10 @@ mod test {
                 rng.gen_range(0. ..=1.),
             );
             let data = hsl_to_rgb(start.0 as f64, start.1 as f64, start.2 as f64);
-            let rgb2 = Hsl::from(start.0, start.1, start.2).to_rgb().as_tuple();
+            let data = (data.0 / 255., data.1 / 255., data.2 / 255.);
+            let rgb2 = Hsl::from(start.0, start.1 * 100., start.2 * 100.)
+                .to_rgb()
+                .as_tuple();
             let rgb2 = (rgb2.0 as f64, rgb2.1 as f64, rgb2.2 as f64);
 
             assert!(
@@ -161,6 +245,202 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_lab() {
+        let mut rng = thread_rng();
+
+        for i in 0..100000 {
+            let start: (f64, f64, f64) = (
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+            );
+            let data = rgb_to_lab(start.0, start.1, start.2);
+            let data = lab_to_rgb(data.0, data.1, data.2);
+            assert!(
+                (start.0 - data.0).abs() <= 0.0000001
+                    && (start.1 - data.1).abs() <= 0.0000001
+                    && (start.2 - data.2).abs() <= 0.0000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+
+            let data = rgb_to_lch(start.0, start.1, start.2);
+            let data = lch_to_rgb(data.0, data.1, data.2);
+            assert!(
+                (start.0 - data.0).abs() <= 0.0000001
+                    && (start.1 - data.1).abs() <= 0.0000001
+                    && (start.2 - data.2).abs() <= 0.0000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_oklab() {
+        let mut rng = thread_rng();
+
+        for i in 0..100000 {
+            let start: (f64, f64, f64) = (
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+            );
+            let data = rgb_to_oklab(start.0, start.1, start.2);
+            let data = oklab_to_rgb(data.0, data.1, data.2);
+            assert!(
+                (start.0 - data.0).abs() <= 0.0000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+            assert!(
+                (start.1 - data.1).abs() <= 0.0000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+            assert!(
+                (start.2 - data.2).abs() <= 0.0000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_oklch() {
+        // Seeded so a failure can be reproduced.
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for i in 0..100000 {
+            let start: (f64, f64, f64) = (
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+            );
+            let data = rgb_to_oklch(start.0, start.1, start.2);
+            let data = oklch_to_rgb(data.0, data.1, data.2);
+            assert!(
+                (start.0 - data.0).abs() <= 0.000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+            assert!(
+                (start.1 - data.1).abs() <= 0.000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+            assert!(
+                (start.2 - data.2).abs() <= 0.000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_oklab_inverses() {
+        assert_eq!(invert_matrix(&LMS_TO_OKLAB), OKLAB_TO_LMS);
+        assert_eq!(invert_matrix(&LINEAR_SRGB_TO_LMS), LMS_TO_LINEAR_SRGB);
+    }
+
+    #[test]
+    fn test_xyz() {
+        let white = rgb_to_xyz(255., 255., 255.);
+        let red = rgb_to_lab(255., 0., 0.);
+
+        assert!((white.0 - 0.9505).abs() < 0.0001);
+        assert!((white.1 - 1.).abs() < 0.0001);
+        assert!((white.2 - 1.089).abs() < 0.0001);
+        assert!((red.0 - 54.29).abs() < 0.01);
+        assert!((red.1 - 80.8).abs() < 0.01);
+        assert!((red.2 - 69.89).abs() < 0.01);
+
+        let red = rgb_to_oklch(255., 0., 0.);
+        assert!((red.0 - 0.62796).abs() < 0.00001);
+        assert!((red.1 - 0.25768).abs() < 0.00001);
+        assert!((red.2 - 29.2339).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        assert_eq!(rgb_to_hsl(0., 0., 255.), (240., 1., 0.5));
+        assert_eq!(rgb_to_hsl(255., 127.5, 127.5), (0., 1., 0.75));
+
+        let mut rng = thread_rng();
+
+        for i in 0..100000 {
+            let start: (f64, f64, f64) = (
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+                rng.gen_range(0. ..=255.),
+            );
+            let data = rgb_to_hsl(start.0, start.1, start.2);
+            let data = hsl_to_rgb(data.0, data.1, data.2);
+            assert!(
+                (start.0 - data.0).abs() <= 0.0000001
+                    && (start.1 - data.1).abs() <= 0.0000001
+                    && (start.2 - data.2).abs() <= 0.0000001,
+                "({}, {}, {}) became ({}, {}, {}) on the {}th iteration",
+                start.0,
+                start.1,
+                start.2,
+                data.0,
+                data.1,
+                data.2,
+                i
+            );
+        }
+    }
+
     /*  #[test]
     // fn test_cmyk() {
     //     let mut rng = thread_rng();
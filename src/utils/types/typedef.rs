@@ -3,10 +3,15 @@ use crate::color_print::NumType;
 
 type StructHandle = wai_bindgen_rust::Handle<crate::Color>;
 
-pub type Wrapper<T> = std::cell::Cell<Option<T>>;
 pub type NewColorResult = Result<StructHandle, crate::color_print::Exeptions>;
 
 pub type RGB = (NumType, NumType, NumType);
 pub type HSL = (NumType, NumType, NumType);
 pub type HSV = (NumType, NumType, NumType);
 pub type CMYK = (NumType, NumType, NumType, NumType);
+pub type XYZ = (NumType, NumType, NumType);
+pub type LAB = (NumType, NumType, NumType);
+pub type LCH = (NumType, NumType, NumType);
+pub type OKLAB = (NumType, NumType, NumType);
+pub type OKLCH = (NumType, NumType, NumType);
+pub type InternelColor = (NumType, NumType, NumType, Option<NumType>, NumType);
@@ -0,0 +1,405 @@
+use crate::color_print::{ColorStandered, Exeptions, NumType, WhitePoint};
+use crate::utils::css::{self, CssColor};
+use crate::utils::types::RGB;
+use crate::utils::{from_rgb, gamut, helper, hex, names, to_rgb};
+
+type Components = (NumType, NumType, NumType, Option<NumType>);
+
+/// An immutable color: the components of one standered along with an alpha.
+///
+/// Unlike the `Color` resource nothing is cached, so conversions return a new value and the
+/// type is `Copy`, `Send` and `Sync`.
+///
+/// ```
+/// use color_print::{ColorStandered, ColorValue, Exeptions, WhitePoint};
+///
+/// let red = ColorValue::rgb(255., 0., 0.)?;
+/// let hsl = red.into_standered(ColorStandered::Hsl);
+/// assert_eq!(hsl.components(), (0., 1., 0.5, None));
+/// assert_eq!(hsl.rgb_value(), (255., 0., 0.));
+///
+/// let gray = ColorValue::lab(50., 0., 0., WhitePoint::D50)?;
+/// assert_eq!(gray.standered(), ColorStandered::Lab);
+///
+/// assert!(matches!(
+///     ColorValue::hsl(400., 1., 0.5),
+///     Err(Exeptions::HueOutOfRange(_))
+/// ));
+/// # Ok::<(), Exeptions>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorValue {
+    standered: ColorStandered,
+    components: Components,
+    alpha: NumType,
+}
+
+impl Default for ColorValue {
+    fn default() -> Self {
+        Self {
+            standered: ColorStandered::None,
+            components: (0., 0., 0., None),
+            alpha: 1.,
+        }
+    }
+}
+
+impl ColorValue {
+    /// A color holding `components` as-is in `standered`, with the black channel of cmyk as the
+    /// 4th element.
+    pub fn new_unchecked(
+        standered: ColorStandered,
+        components: Components,
+        alpha: NumType,
+    ) -> Self {
+        let components = match standered {
+            ColorStandered::Cmyk => (
+                components.0,
+                components.1,
+                components.2,
+                Some(components.3.unwrap_or_default()),
+            ),
+            ColorStandered::None => (0., 0., 0., None),
+            _ => (components.0, components.1, components.2, None),
+        };
+
+        Self {
+            standered,
+            components,
+            alpha,
+        }
+    }
+
+    fn opaque(standered: ColorStandered, (a, b, c): (NumType, NumType, NumType)) -> Self {
+        Self::new_unchecked(standered, (a, b, c, None), 1.)
+    }
+
+    pub fn cmyk(cyan: f64, magenta: f64, yellow: f64, black: f64) -> Result<Self, Exeptions> {
+        if !(0. ..=1.).contains(&cyan) {
+            return Err(Exeptions::CyanOutOfRange(cyan));
+        } else if !(0. ..=1.).contains(&magenta) {
+            return Err(Exeptions::MagentaOutOfRange(magenta));
+        } else if !(0. ..=1.).contains(&yellow) {
+            return Err(Exeptions::YellowOutOfRange(yellow));
+        } else if !(0. ..=1.).contains(&black) {
+            return Err(Exeptions::BlackOutOfRange(black));
+        }
+
+        Ok(Self::new_unchecked(
+            ColorStandered::Cmyk,
+            (cyan, magenta, yellow, Some(black)),
+            1.,
+        ))
+    }
+
+    pub fn hex(value: &str) -> Result<Self, Exeptions> {
+        let hex = hex::parse(value)?;
+
+        Ok(Self::opaque(ColorStandered::Rgb, hex.rgb).with_alpha_unchecked(hex.alpha))
+    }
+
+    pub fn css(value: &str) -> Result<Self, Exeptions> {
+        let value = value.trim();
+
+        if value.starts_with('#') {
+            return Self::hex(value);
+        }
+
+        let parsed = css::parse(value)?;
+        let color = match parsed.color {
+            CssColor::Rgb(rgb) => Self::opaque(ColorStandered::Rgb, rgb),
+            CssColor::Hsl(hsl) => Self::opaque(ColorStandered::Hsl, hsl),
+        };
+
+        Ok(color.with_alpha_unchecked(parsed.alpha))
+    }
+
+    /// A color from the CSS keywords, then the X11 names, then the curated xkcd subset.
+    pub fn name(name: &str) -> Result<Self, Exeptions> {
+        let rgb = names::lookup(name).ok_or_else(|| Exeptions::UnknownColorName(name.into()))?;
+
+        Ok(Self::opaque(ColorStandered::Rgb, rgb))
+    }
+
+    pub fn hsl(hue: f64, sateration: f64, lightness: f64) -> Result<Self, Exeptions> {
+        if !(0. ..=360.).contains(&hue) {
+            return Err(Exeptions::HueOutOfRange(hue));
+        } else if !(0. ..=1.).contains(&sateration) {
+            return Err(Exeptions::SaterationOutOfRange(sateration));
+        } else if !(0. ..=1.).contains(&lightness) {
+            return Err(Exeptions::LightnessOutOfRange(lightness));
+        }
+
+        Ok(Self::opaque(
+            ColorStandered::Hsl,
+            (hue, sateration, lightness),
+        ))
+    }
+
+    pub fn hsv(hue: f64, sateration: f64, value: f64) -> Result<Self, Exeptions> {
+        if !(0. ..=360.).contains(&hue) {
+            return Err(Exeptions::HueOutOfRange(hue));
+        } else if !(0. ..=1.).contains(&sateration) {
+            return Err(Exeptions::SaterationOutOfRange(sateration));
+        } else if !(0. ..=1.).contains(&value) {
+            return Err(Exeptions::LightnessOutOfRange(value));
+        }
+
+        Ok(Self::opaque(ColorStandered::Hsv, (hue, sateration, value)))
+    }
+
+    pub fn xyz(x: f64, y: f64, z: f64) -> Result<Self, Exeptions> {
+        for value in [x, y, z] {
+            if !(0. ..=NumType::MAX).contains(&value) {
+                return Err(Exeptions::XyzOutOfRange(value));
+            }
+        }
+
+        Ok(Self::opaque(ColorStandered::Xyz, (x, y, z)))
+    }
+
+    /// Lab relative to `white_point`, stored relative to D50.
+    pub fn lab(lightness: f64, a: f64, b: f64, white_point: WhitePoint) -> Result<Self, Exeptions> {
+        if !(0. ..=100.).contains(&lightness) {
+            return Err(Exeptions::LabLightnessOutOfRange(lightness));
+        }
+
+        let xyz = helper::lab_to_xyz_d65((lightness, a, b), white_point);
+
+        Ok(Self::opaque(
+            ColorStandered::Lab,
+            helper::xyz_d65_to_lab(xyz, WhitePoint::D50),
+        ))
+    }
+
+    /// Lch relative to `white_point`, stored relative to D50.
+    pub fn lch(
+        lightness: f64,
+        chroma: f64,
+        hue: f64,
+        white_point: WhitePoint,
+    ) -> Result<Self, Exeptions> {
+        if !(0. ..=100.).contains(&lightness) {
+            return Err(Exeptions::LabLightnessOutOfRange(lightness));
+        } else if !(0. ..=NumType::MAX).contains(&chroma) {
+            return Err(Exeptions::ChromaOutOfRange(chroma));
+        } else if !(0. ..=360.).contains(&hue) {
+            return Err(Exeptions::HueOutOfRange(hue));
+        }
+
+        let lab = helper::from_polar((lightness, chroma, hue));
+        let xyz = helper::lab_to_xyz_d65(lab, white_point);
+        let lab = helper::xyz_d65_to_lab(xyz, WhitePoint::D50);
+
+        Ok(Self::opaque(ColorStandered::Lch, helper::to_polar(lab)))
+    }
+
+    pub fn oklab(lightness: f64, a: f64, b: f64) -> Result<Self, Exeptions> {
+        if !(0. ..=1.).contains(&lightness) {
+            return Err(Exeptions::OklabLightnessOutOfRange(lightness));
+        } else if !(-0.5..=0.5).contains(&a) {
+            return Err(Exeptions::OklabAOutOfRange(a));
+        } else if !(-0.5..=0.5).contains(&b) {
+            return Err(Exeptions::OklabBOutOfRange(b));
+        }
+
+        Ok(Self::opaque(ColorStandered::Oklab, (lightness, a, b)))
+    }
+
+    pub fn oklch(lightness: f64, chroma: f64, hue: f64) -> Result<Self, Exeptions> {
+        if !(0. ..=1.).contains(&lightness) {
+            return Err(Exeptions::OklabLightnessOutOfRange(lightness));
+        } else if !(0. ..=0.5).contains(&chroma) {
+            return Err(Exeptions::OklchChromaOutOfRange(chroma));
+        } else if !(0. ..=360.).contains(&hue) {
+            return Err(Exeptions::HueOutOfRange(hue));
+        }
+
+        Ok(Self::opaque(
+            ColorStandered::Oklch,
+            (lightness, chroma, hue),
+        ))
+    }
+
+    pub fn rgb(red: f64, green: f64, blue: f64) -> Result<Self, Exeptions> {
+        if !(0. ..=255.).contains(&red) {
+            return Err(Exeptions::RedOutOfRange(red));
+        } else if !(0. ..=255.).contains(&green) {
+            return Err(Exeptions::GreenOutOfRange(green));
+        } else if !(0. ..=255.).contains(&blue) {
+            return Err(Exeptions::BlueOutOfRange(blue));
+        }
+
+        Ok(Self::opaque(ColorStandered::Rgb, (red, green, blue)))
+    }
+
+    pub fn standered(&self) -> ColorStandered {
+        self.standered
+    }
+
+    /// The stored components, with the black channel of cmyk as the 4th element.
+    pub fn components(&self) -> Components {
+        self.components
+    }
+
+    pub fn alpha(&self) -> NumType {
+        self.alpha
+    }
+
+    pub fn with_alpha(self, alpha: NumType) -> Result<Self, Exeptions> {
+        if !(0. ..=1.).contains(&alpha) {
+            return Err(Exeptions::AlphaOutOfRange(alpha));
+        }
+
+        Ok(self.with_alpha_unchecked(alpha))
+    }
+
+    fn with_alpha_unchecked(self, alpha: NumType) -> Self {
+        Self { alpha, ..self }
+    }
+
+    /// The color as sRGB.
+    pub fn rgb_value(&self) -> RGB {
+        let (a, b, c, black) = self.components;
+
+        match self.standered {
+            ColorStandered::Rgb => (a, b, c),
+            ColorStandered::Cmyk => to_rgb::cmyk_to_rgb(a, b, c, black.unwrap_or_default()),
+            ColorStandered::Hsl => to_rgb::hsl_to_rgb(a, b, c),
+            ColorStandered::Hsv => to_rgb::hsv_to_rgb(a, b, c),
+            ColorStandered::Xyz => to_rgb::xyz_to_rgb(a, b, c),
+            ColorStandered::Lab => to_rgb::lab_to_rgb(a, b, c),
+            ColorStandered::Lch => to_rgb::lch_to_rgb(a, b, c),
+            ColorStandered::Oklab => to_rgb::oklab_to_rgb(a, b, c),
+            ColorStandered::Oklch => to_rgb::oklch_to_rgb(a, b, c),
+            ColorStandered::None => (0., 0., 0.),
+        }
+    }
+
+    /// The color brought into sRGB with the CSS Color Level 4 gamut mapping, in the same standered.
+    pub fn gamut_mapped(self) -> Self {
+        if self.standered == ColorStandered::None || gamut::in_gamut(self.rgb_value()) {
+            return self;
+        }
+
+        let (lightness, chroma, hue, _) = self.into_standered(ColorStandered::Oklch).components;
+        let (red, green, blue) = gamut::map_oklch((lightness, chroma, hue));
+
+        Self::new_unchecked(ColorStandered::Rgb, (red, green, blue, None), self.alpha)
+            .into_standered(self.standered)
+    }
+
+    /// The same color in `standered`, keeping the alpha.
+    ///
+    /// Lab and Lch, and Oklab and Oklch, convert into each other directly; everything else goes
+    /// through sRGB.
+    pub fn into_standered(self, standered: ColorStandered) -> Self {
+        let three = |(a, b, c): (NumType, NumType, NumType)| (a, b, c, None);
+        let (a, b, c, _) = self.components;
+
+        let components = match (self.standered, standered) {
+            (ColorStandered::None, _) | (_, ColorStandered::None) => (0., 0., 0., None),
+            (from, to) if from == to => return self,
+            (ColorStandered::Lab, ColorStandered::Lch)
+            | (ColorStandered::Oklab, ColorStandered::Oklch) => three(helper::to_polar((a, b, c))),
+            (ColorStandered::Lch, ColorStandered::Lab)
+            | (ColorStandered::Oklch, ColorStandered::Oklab) => {
+                three(helper::from_polar((a, b, c)))
+            }
+            (_, to) => {
+                let (red, green, blue) = self.rgb_value();
+
+                match to {
+                    ColorStandered::Cmyk => {
+                        let cmyk = from_rgb::rgb_to_cmyk(red, green, blue);
+                        (cmyk.0, cmyk.1, cmyk.2, Some(cmyk.3))
+                    }
+                    ColorStandered::Hsl => three(from_rgb::rgb_to_hsl(red, green, blue)),
+                    ColorStandered::Hsv => three(from_rgb::rgb_to_hsv(red, green, blue)),
+                    ColorStandered::Xyz => three(from_rgb::rgb_to_xyz(red, green, blue)),
+                    ColorStandered::Lab => three(from_rgb::rgb_to_lab(red, green, blue)),
+                    ColorStandered::Lch => three(from_rgb::rgb_to_lch(red, green, blue)),
+                    ColorStandered::Oklab => three(from_rgb::rgb_to_oklab(red, green, blue)),
+                    ColorStandered::Oklch => three(from_rgb::rgb_to_oklch(red, green, blue)),
+                    _ => (red, green, blue, None),
+                }
+            }
+        };
+
+        Self {
+            standered,
+            components,
+            alpha: self.alpha,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync + Copy>() {}
+
+    #[test]
+    fn thread_safe() {
+        assert_send_sync::<ColorValue>();
+
+        let palette = [
+            ColorValue::rgb(255., 0., 0.).unwrap(),
+            ColorValue::hsl(120., 1., 0.5).unwrap(),
+        ];
+        let handle = std::thread::spawn(move || palette.map(|color| color.rgb_value()));
+
+        assert_eq!(handle.join().unwrap(), [(255., 0., 0.), (0., 255., 0.)]);
+    }
+
+    #[test]
+    fn conversions_return_new_values() {
+        let red = ColorValue::rgb(255., 0., 0.)
+            .unwrap()
+            .with_alpha(0.5)
+            .unwrap();
+        let hsl = red.into_standered(ColorStandered::Hsl);
+
+        assert_eq!(red.standered(), ColorStandered::Rgb);
+        assert_eq!(red.components(), (255., 0., 0., None));
+        assert_eq!(hsl.standered(), ColorStandered::Hsl);
+        assert_eq!(hsl.components(), (0., 1., 0.5, None));
+        assert_eq!(hsl.alpha(), 0.5);
+        assert_eq!(hsl.into_standered(ColorStandered::Rgb), red);
+        assert_eq!(red.into_standered(ColorStandered::Rgb), red);
+
+        let cmyk = red.into_standered(ColorStandered::Cmyk);
+        assert_eq!(cmyk.components(), (0., 1., 1., Some(0.)));
+
+        let oklch = ColorValue::oklab(0.5, 0., 0.1)
+            .unwrap()
+            .into_standered(ColorStandered::Oklch);
+        assert_eq!(oklch.standered(), ColorStandered::Oklch);
+        assert!((oklch.components().2 - 90.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn validation() {
+        assert!(matches!(
+            ColorValue::rgb(256., 0., 0.),
+            Err(Exeptions::RedOutOfRange(_))
+        ));
+        assert!(matches!(
+            ColorValue::hsl(0., 2., 0.),
+            Err(Exeptions::SaterationOutOfRange(_))
+        ));
+        assert!(matches!(
+            ColorValue::rgb(0., 0., 0.).unwrap().with_alpha(1.5),
+            Err(Exeptions::AlphaOutOfRange(_))
+        ));
+        assert_eq!(
+            ColorValue::hex("#ff000080").unwrap().components(),
+            (255., 0., 0., None)
+        );
+        assert_eq!(
+            ColorValue::name("rebeccapurple").unwrap().rgb_value(),
+            (102., 51., 153.)
+        );
+    }
+}